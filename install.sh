@@ -87,17 +87,26 @@ setup_shell() {
         sed -i.bak '/alias node=/d' "$SHELL_CONFIG"
         sed -i.bak '/alias npm=/d' "$SHELL_CONFIG"
         sed -i.bak '/alias npx=/d' "$SHELL_CONFIG"
+        sed -i.bak '/rnvm env/d' "$SHELL_CONFIG"
+        sed -i.bak '/set -gx PATH $PATH $HOME\/.rnvm\/bin/d' "$SHELL_CONFIG"
     fi
 
     # Add new configuration
-    {
-        echo ""
-        echo "# rnvm configuration"
-        echo 'export PATH="$PATH:$HOME/.rnvm/bin"'
-        echo 'alias node="$HOME/.rnvm/current/bin/node"'
-        echo 'alias npm="$HOME/.rnvm/current/bin/npm"'
-        echo 'alias npx="$HOME/.rnvm/current/bin/npx"'
-    } >> "$SHELL_CONFIG"
+    if [[ "$SHELL_CONFIG" == *config.fish ]]; then
+        {
+            echo ""
+            echo "# rnvm configuration"
+            echo 'set -gx PATH $PATH $HOME/.rnvm/bin'
+            echo 'rnvm env --shell fish | source'
+        } >> "$SHELL_CONFIG"
+    else
+        {
+            echo ""
+            echo "# rnvm configuration"
+            echo 'export PATH="$PATH:$HOME/.rnvm/bin"'
+            echo 'eval "$(rnvm env)"'
+        } >> "$SHELL_CONFIG"
+    fi
 
     print_success "Shell configured successfully!"
 }
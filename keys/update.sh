@@ -0,0 +1,16 @@
+#!/bin/sh
+# Regenerates nodejs-release-keys.asc from https://github.com/nodejs/release-keys.
+# Review the resulting diff before committing: these keys are what rnvm trusts by default.
+set -eu
+
+base="https://raw.githubusercontent.com/nodejs/release-keys/HEAD"
+out="$(dirname "$0")/nodejs-release-keys.asc"
+tmp="$out.tmp"
+
+: > "$tmp"
+for fingerprint in $(curl -fsSL "$base/keys.list"); do
+    curl -fsSL "$base/keys/$fingerprint.asc" >> "$tmp"
+done
+
+grep -q -- "-----BEGIN PGP PUBLIC KEY BLOCK-----" "$tmp"
+mv "$tmp" "$out"
@@ -1,5 +1,6 @@
-use crate::errors::Result;
+use crate::errors::{NodeError, Result};
 use clap::{Parser, Subcommand};
+use clap_complete::engine::ArgValueCandidates;
 use colored::Colorize;
 
 #[derive(Parser)]
@@ -18,24 +19,56 @@ pub struct Cli {
     )]
     pub verbose: u8,
 
+    #[arg(
+        long,
+        global = true,
+        help = "Verify the SHASUMS256.txt signature against the Node.js release keys"
+    )]
+    pub verify_signature: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "URL",
+        help = "Node.js dist mirror to use instead of https://nodejs.org/dist"
+    )]
+    pub mirror: Option<String>,
+
+    #[arg(long, global = true, help = "Resolve versions from the local cache only")]
+    pub offline: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
-    #[command(about = "Install a Node.js version", long_about = "Install a Node.js version. Examples:\n  rnvm install lts     # Install latest LTS\n  rnvm install 20.9.0  # Install specific version\n  rnvm install 20      # Install latest from major version")]
+    #[command(about = "Install a Node.js version", long_about = "Install a Node.js version. Examples:\n  rnvm install lts     # Install latest LTS\n  rnvm install 20.9.0  # Install specific version\n  rnvm install 20      # Install latest from major version\n  rnvm install lts/iron  # Install latest from an LTS line\n  rnvm install lts/-1  # Install the LTS line before the latest\n  rnvm install         # Install the version from .nvmrc / package.json")]
     Install {
-        #[arg(help = "Version to install (e.g., '20.9.0', '18', 'lts', 'latest')")]
-        version: String,
+        #[arg(
+            help = "Version to install (e.g., '20.9.0', '18', 'lts', 'lts/iron', 'latest'). Defaults to the project's version file",
+            add = ArgValueCandidates::new(crate::shell::completions::lts_specs)
+        )]
+        version: Option<String>,
+        #[arg(long, help = "Reinstall versions left incomplete by an interrupted install (only VERSION, if given)")]
+        repair: bool,
     },
 
     #[command(about = "Switch to a Node.js version")]
     Use {
-        #[arg(help = "Version or alias to use (e.g., '20.9.0', 'lts', 'stable')")]
-        version: String,
+        #[arg(
+            help = "Version or alias to use (e.g., '20.9.0', 'lts', 'stable'). Defaults to the project's version file",
+            add = ArgValueCandidates::new(crate::shell::completions::version_specs)
+        )]
+        version: Option<String>,
         #[arg(short, long, help = "Set this version as the default")]
         default: bool,
+        #[arg(long, help = "Print nothing on success, and only a one-line hint instead of an error when the version is missing")]
+        silent: bool,
+        #[arg(long, help = "Do nothing if the resolved version is already active")]
+        if_changed: bool,
+        #[arg(long, help = "Install the version if it is not installed yet")]
+        install_if_missing: bool,
     },
 
     #[command(about = "List Node.js versions")]
@@ -44,6 +77,8 @@ pub enum Commands {
         remote: bool,
         #[arg(short, long, help = "Show only LTS versions")]
         lts: bool,
+        #[arg(long, help = "Show only security releases (with --remote)")]
+        security: bool,
     },
 
     #[command(about = "Create an alias for a version")]
@@ -56,7 +91,7 @@ pub enum Commands {
 
     #[command(about = "Remove an alias")]
     Unalias {
-        #[arg(help = "Name of the alias to remove")]
+        #[arg(help = "Name of the alias to remove", add = ArgValueCandidates::new(crate::shell::completions::alias_names))]
         name: String,
     },
 
@@ -74,54 +109,245 @@ pub enum Commands {
 
     #[command(about = "Remove a Node.js version")]
     Uninstall {
-        #[arg(help = "Version to remove")]
+        #[arg(help = "Version to remove", add = ArgValueCandidates::new(crate::shell::completions::installed_versions))]
         version: String,
     },
+
+    #[command(
+        about = "Print shell commands that put rnvm's Node.js on PATH",
+        long_about = "Print shell commands that put rnvm's Node.js on PATH. Add one line to your shell config:\n  bash/zsh:    eval \"$(rnvm env)\"\n  fish:        rnvm env | source\n  PowerShell:  rnvm env --shell powershell | Out-String | Invoke-Expression\n  nushell:     let rnvm = (rnvm env --json | from json); load-env $rnvm.env; $env.PATH = ($env.PATH | prepend $rnvm.path)"
+    )]
+    Env {
+        #[arg(long, value_enum, help = "Shell to generate commands for (detected by default)")]
+        shell: Option<crate::shell::Shell>,
+        #[arg(long, help = "Switch versions automatically when entering a directory with a version file")]
+        use_on_cd: bool,
+        #[arg(long, requires = "use_on_cd", help = "Let the directory hook install missing versions")]
+        install_if_missing: bool,
+        #[arg(long, conflicts_with_all = ["shell", "use_on_cd"], help = "Print the variables and PATH entry as JSON, for shells such as nushell that cannot eval a script")]
+        json: bool,
+    },
+
+    #[command(
+        about = "Run a command with a Node.js version on PATH, without switching",
+        long_about = "Run a command with a Node.js version's bin directory first on PATH, without switching the active version. Examples:\n  rnvm exec 18 -- npm test\n  rnvm exec -- node -v   # Uses the version from .nvmrc / package.json"
+    )]
+    Exec {
+        #[arg(help = "Version to run with. Defaults to the project's version file", add = ArgValueCandidates::new(crate::shell::completions::version_specs))]
+        version: Option<String>,
+        #[arg(last = true, required = true, help = "Command and arguments to run")]
+        command: Vec<String>,
+    },
+
+    #[command(about = "Run a script with node from a specific version", long_about = "Run node from a specific version. Example:\n  rnvm run 18 script.js --flag")]
+    Run {
+        #[arg(help = "Version of node to run", add = ArgValueCandidates::new(crate::shell::completions::version_specs))]
+        version: String,
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true, help = "Arguments passed to node")]
+        args: Vec<String>,
+    },
+
+    #[command(
+        about = "Print a shell completion script",
+        long_about = "Print a completion script that also completes installed versions, aliases and lts/<codename>. Examples:\n  bash:        echo 'source <(rnvm completions bash)' >> ~/.bashrc\n  zsh:         echo 'source <(rnvm completions zsh)' >> ~/.zshrc\n  fish:        rnvm completions fish > ~/.config/fish/completions/rnvm.fish\n  PowerShell:  rnvm completions powershell | Out-String | Invoke-Expression\n  elvish:      eval (rnvm completions elvish | slurp)"
+    )]
+    Completions {
+        #[arg(value_parser = crate::shell::completions::SHELLS, help = "Shell to generate the script for")]
+        shell: String,
+    },
+
+    #[command(
+        about = "Import Node.js installs from another version manager",
+        long_about = "Import the Node.js versions installed by nvm, fnm, n or Volta, and nvm's aliases. Files are copied; pass --hardlink to share them with the source instead. Example:\n  rnvm import --from nvm"
+    )]
+    Import {
+        #[arg(long, value_enum, help = "Version manager to import from")]
+        from: crate::import::ImportSource,
+        #[arg(long, help = "Hardlink files instead of copying them. Saves space, but the installs then share files with the source")]
+        hardlink: bool,
+    },
+
+    #[command(about = "Check the rnvm setup for common problems")]
+    Doctor {
+        #[arg(long, help = "Repair problems that can be fixed safely")]
+        fix: bool,
+        #[arg(long, help = "Print the results as JSON")]
+        json: bool,
+    },
+
+    #[command(about = "Manage the cached Node.js version index")]
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
+    },
+
+    #[command(about = "Manage the node/npm shims used outside of rnvm-enabled shells")]
+    Shims {
+        #[command(subcommand)]
+        command: ShimsCommands,
+    },
+
+    #[command(about = "Manage the Node.js release signing keys")]
+    Keys {
+        #[command(subcommand)]
+        command: KeysCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CacheCommands {
+    #[command(about = "Download the latest version index now")]
+    Refresh,
+    #[command(about = "Delete the cached version index")]
+    Clear,
+}
+
+#[derive(Subcommand)]
+pub enum ShimsCommands {
+    #[command(about = "Regenerate shims, including binaries from global packages")]
+    Rebuild,
+}
+
+#[derive(Subcommand)]
+pub enum KeysCommands {
+    #[command(about = "Download the current Node.js release keys, replacing the bundled ones")]
+    Update,
 }
 
 pub async fn run_with(cli: Cli) -> Result<()> {
+    crate::config::settings::set_cli_overrides(crate::config::settings::CliOverrides {
+        verify_signature: cli.verify_signature,
+        mirror: cli.mirror.clone(),
+        offline: cli.offline,
+    });
+
+    let _ = crate::installer::extract::clean_stale_staging_dirs();
+
+    let verbose = cli.verbose > 0;
+
     match cli.command {
-        Commands::Install { version } => {
+        Commands::Install { version, repair: true } => {
+            let broken = crate::version::local::get_broken_versions()?;
+            let to_repair: Vec<String> = match version {
+                Some(spec) => match crate::version::find_broken_versions(&spec) {
+                    Ok(selected) => selected,
+                    Err(NodeError::VersionNotFound(_)) => Vec::new(),
+                    Err(e) => return Err(e),
+                },
+                None => broken,
+            };
+
+            if to_repair.is_empty() {
+                println!("✓ No broken installs to repair");
+            }
+            for version in to_repair {
+                let resolved_version = crate::version::get_matching_version(&version).await?;
+                println!("Repairing Node.js {}...", version);
+                crate::installer::install_version(&resolved_version).await?;
+                println!("✓ Repaired Node.js {}", version.green());
+            }
+        }
+
+        Commands::Install { version, repair: false } => {
+            let version = match version {
+                Some(version) => version,
+                None => {
+                    let found = project_version_file()?;
+                    println!("Found version {} in {}", found.version.green(), found.path.display());
+                    found.version
+                }
+            };
             let resolved_version = crate::version::get_matching_version(&version).await?;
             println!("Installing Node.js {}...", resolved_version.version_str());
             crate::installer::install_version(&resolved_version).await?;
             println!("✓ Installed Node.js {}", resolved_version.version_str().green());
         }
 
-        Commands::Use { version, default } => {
-            let version_str = if let Some(aliased) = crate::config::alias::get_alias(&version)? {
-                aliased
-            } else {
-                version.clone()
+        Commands::Use { version, default, silent, if_changed, install_if_missing } => {
+            let version = match version {
+                Some(version) => version,
+                None => match crate::config::local::find_version_file() {
+                    Some(found) => {
+                        if !silent {
+                            println!("Found version {} in {}", found.version.green(), found.path.display());
+                        }
+                        found.version
+                    }
+                    None if silent => return Ok(()),
+                    None => return Err(no_version_file_error()),
+                },
             };
 
-            if !crate::version::local::is_installed(&version_str)? {
-                println!("Version {} is not installed. Installing...", version_str);
-                let resolved = crate::version::get_matching_version(&version_str).await?;
-                crate::installer::install_version(&resolved).await?;
-            }
+            let version_str = match crate::version::find_installed_version(&version)? {
+                Some(installed) => installed.version_str(),
+                None if silent && !install_if_missing => {
+                    eprintln!("rnvm: Node.js {} is not installed. Run 'rnvm install' to add it.", version);
+                    return Ok(());
+                }
+                None => {
+                    let resolved = crate::version::get_matching_version(&version).await?;
+                    if !silent {
+                        println!("Version {} is not installed. Installing...", resolved.version_str());
+                    }
+                    crate::installer::install_version(&resolved).await?;
+                    resolved.version_str()
+                }
+            };
 
-            crate::version::local::use_version(&version_str)?;
-            println!("✓ Now using Node.js {}", version_str.green());
+            let unchanged = crate::version::local::get_current_version()?.as_deref() == Some(version_str.as_str());
+            if !(if_changed && unchanged) {
+                crate::version::local::use_version(&version_str)?;
+                if !silent {
+                    println!("✓ Now using Node.js {}", version_str.green());
+                }
+            }
 
             if default {
+                crate::version::local::set_global_version(&version_str)?;
                 crate::config::alias::set_alias("default", &version_str)?;
-                println!("✓ Set {} as default version", version_str.green());
+                if !silent {
+                    println!("✓ Set {} as default version", version_str.green());
+                }
             }
         }
 
-        Commands::List { remote, lts } => {
+        Commands::List { remote, lts, security } => {
             if remote {
                 let versions = crate::version::remote::fetch_version_list().await?;
                 let versions: Vec<_> = versions
                     .into_iter()
                     .filter(|v| !lts || v.lts)
+                    .filter(|v| !security || v.security)
                     .collect();
 
                 println!("Remote versions available:");
                 for v in versions {
-                    let lts_marker = if v.lts { " (LTS)".yellow() } else { "".normal() };
-                    println!("  {}{}", v.version_str().green(), lts_marker);
+                    let npm = v.npm.as_deref().map(|npm| format!("npm {}", npm)).unwrap_or_default();
+                    let lts_marker = match (&v.lts_codename, v.lts) {
+                        (Some(codename), _) => format!(" (LTS: {})", codename).yellow(),
+                        (None, true) => " (LTS)".yellow(),
+                        (None, false) => "".normal(),
+                    };
+                    let security_marker = if v.security { " [security]".red() } else { "".normal() };
+                    let details = if verbose {
+                        format!(
+                            "  v8 {}  modules {}  openssl {}",
+                            v.v8.as_deref().unwrap_or("-"),
+                            v.modules.as_deref().unwrap_or("-"),
+                            v.openssl.as_deref().unwrap_or("-")
+                        )
+                    } else {
+                        String::new()
+                    };
+                    println!(
+                        "  {} {}  {}{}{}{}",
+                        format!("{:<10}", v.version_str()).green(),
+                        v.date,
+                        format!("{:<12}", npm).dimmed(),
+                        lts_marker,
+                        security_marker,
+                        details.dimmed()
+                    );
                 }
             } else {
                 if crate::version::local::is_empty() {
@@ -132,6 +358,18 @@ pub async fn run_with(cli: Cli) -> Result<()> {
                 let versions = crate::version::local::get_installed_versions()?;
                 let aliases = crate::config::alias::list_aliases()?;
 
+                let broken = crate::version::local::get_broken_versions()?;
+                if !broken.is_empty() {
+                    eprintln!(
+                        "{}",
+                        format!(
+                            "Hiding incomplete installs: {}. Run 'rnvm doctor --fix' to adopt installs from an earlier rnvm, or 'rnvm install --repair' to reinstall them.",
+                            broken.join(", ")
+                        )
+                        .yellow()
+                    );
+                }
+
                 println!("Installed versions:");
                 for ver in versions {
                     let prefix = if ver.is_current {
@@ -182,8 +420,20 @@ pub async fn run_with(cli: Cli) -> Result<()> {
         }
 
         Commands::Which => {
-            if let Some((file, version)) = crate::config::local::find_version_file() {
-                println!("Found version {} in {}", version.green(), file.display());
+            if let Some(found) = crate::config::local::find_version_file() {
+                let version = found.version;
+                println!("Found version {} in {} ({})", version.green(), found.path.display(), found.source);
+                match crate::version::find_installed_version(&version)? {
+                    Some(installed) => println!("Resolves to {} (installed)", installed.version_str().green()),
+                    None => match crate::version::get_matching_version(&version).await {
+                        Ok(remote) => println!(
+                            "Resolves to {} (not installed, run 'rnvm install {}')",
+                            remote.version_str().yellow(),
+                            remote.version_str()
+                        ),
+                        Err(e) => println!("Could not resolve {}: {}", version, e),
+                    },
+                }
             } else if let Some(version) = crate::version::local::get_current_version()? {
                 println!("Using global version: {}", version.green());
             } else if let Some(version) = crate::config::alias::get_alias("default")? {
@@ -194,10 +444,138 @@ pub async fn run_with(cli: Cli) -> Result<()> {
         }
 
         Commands::Uninstall { version } => {
-            crate::version::local::remove_version(&version)?;
+            crate::version::local::remove_version(&version, &crate::version::local::get_current_link())?;
             println!("✓ Uninstalled Node.js {}", version.green());
         }
+
+        Commands::Env { shell, use_on_cd, install_if_missing, json } => {
+            crate::shell::multishell::collect_garbage()?;
+            let session = crate::shell::multishell::create_session()?;
+            let env = crate::shell::env::ShellEnv::new(&crate::utils::get_base_dir(), &session);
+            if json {
+                println!("{}", crate::shell::env::render_json(&env));
+                return Ok(());
+            }
+
+            let shell = match shell {
+                Some(shell) => shell,
+                None => crate::shell::Shell::detect()?,
+            };
+            print!("{}", crate::shell::env::render(shell, &env));
+            if use_on_cd {
+                print!("{}", crate::shell::env::render_use_on_cd(shell, install_if_missing));
+            }
+        }
+
+        Commands::Exec { version, command } => {
+            let version = project_version_or(version)?;
+            let version_str = crate::installer::ensure_installed(&version).await?;
+            crate::exec::run_with_version(&version_str, &command[0], &command[1..])?;
+        }
+
+        Commands::Run { version, args } => {
+            let version_str = crate::installer::ensure_installed(&version).await?;
+            crate::exec::run_with_version(&version_str, "node", &args)?;
+        }
+
+        Commands::Completions { shell } => {
+            crate::shell::completions::write_registration(&shell, &mut std::io::stdout())?;
+        }
+
+        Commands::Import { from, hardlink } => {
+            let report = crate::import::import_from(from, hardlink)?;
+
+            for version in &report.versions {
+                println!("✓ Imported Node.js {}", version.green());
+            }
+            for (name, version) in &report.aliases {
+                println!("✓ Imported alias {} → {}", name.green(), version.yellow());
+            }
+            for (item, reason) in &report.skipped {
+                println!("{} {}: {}", "Skipped".yellow(), item, reason);
+            }
+            if report.versions.is_empty() && report.aliases.is_empty() && report.skipped.is_empty() {
+                println!("Nothing to import");
+            }
+        }
+
+        Commands::Doctor { fix, json } => {
+            let checks = crate::doctor::run_checks(fix);
+
+            if json {
+                let output = serde_json::to_string_pretty(&checks)
+                    .map_err(|e| NodeError::ConfigError(format!("Failed to serialize doctor results: {}", e)))?;
+                println!("{}", output);
+            } else {
+                for check in &checks {
+                    let status = match (check.status, check.fixed) {
+                        (_, true) => "fixed".green(),
+                        (crate::doctor::Status::Pass, _) => "pass ".green(),
+                        (crate::doctor::Status::Warn, _) => "warn ".yellow(),
+                        (crate::doctor::Status::Fail, _) => "fail ".red(),
+                    };
+                    println!("{} {:<12} {}", status, check.name, check.message);
+                    if let Some(hint) = &check.hint {
+                        println!("      {:<12} {}", "", format!("→ {}", hint).dimmed());
+                    }
+                }
+            }
+
+            let failed = checks.iter().filter(|c| c.status == crate::doctor::Status::Fail).count();
+            if failed > 0 {
+                return Err(NodeError::SystemError(format!("{} doctor check(s) failed", failed)));
+            }
+        }
+
+        Commands::Cache { command } => match command {
+            CacheCommands::Refresh => {
+                crate::version::cache::refresh().await?;
+                println!("✓ Refreshed version index cache");
+            }
+            CacheCommands::Clear => {
+                crate::version::cache::clear()?;
+                println!("✓ Cleared version index cache");
+            }
+        },
+
+        Commands::Shims { command } => match command {
+            ShimsCommands::Rebuild => {
+                let names = crate::shims::rebuild()?;
+                println!("✓ Created {} shims in {}", names.len(), crate::shims::get_shims_dir().display());
+                if verbose {
+                    for name in names {
+                        println!("  {}", name);
+                    }
+                }
+            }
+        },
+
+        Commands::Keys { command } => match command {
+            KeysCommands::Update => {
+                let keyring = crate::config::settings::release_keyring()?;
+                let count = crate::installer::signature::update_keyring(&keyring).await?;
+                println!("✓ Saved {} release keys to {}", count, keyring.display());
+            }
+        },
     }
 
     Ok(())
+}
+
+/// Falls back to the project's version file when no version was given.
+fn project_version_or(version: Option<String>) -> Result<String> {
+    match version {
+        Some(version) => Ok(version),
+        None => project_version_file().map(|found| found.version),
+    }
+}
+
+fn project_version_file() -> Result<crate::config::local::VersionFile> {
+    crate::config::local::find_version_file().ok_or_else(no_version_file_error)
+}
+
+fn no_version_file_error() -> NodeError {
+    NodeError::ConfigError(
+        "No version given and no version file (.nvmrc, .node-version, .tool-versions, package.json) found".to_string(),
+    )
 }
\ No newline at end of file
@@ -51,6 +51,7 @@ fn save_aliases(config: &AliasConfig) -> Result<()> {
 }
 
 pub fn set_alias(name: &str, version: &str) -> Result<()> {
+    let _lock = crate::lock::lock_config()?;
     let mut config = load_aliases()?;
     config.aliases.insert(name.to_string(), version.to_string());
     save_aliases(&config)?;
@@ -58,6 +59,7 @@ pub fn set_alias(name: &str, version: &str) -> Result<()> {
 }
 
 pub fn remove_alias(name: &str) -> Result<()> {
+    let _lock = crate::lock::lock_config()?;
     let mut config = load_aliases()?;
     if config.aliases.remove(name).is_none() {
         return Err(NodeError::AliasError(format!("Alias '{}' not found", name)));
@@ -66,6 +68,15 @@ pub fn remove_alias(name: &str) -> Result<()> {
     Ok(())
 }
 
+/// Moves an unreadable `aliases.json` aside so a fresh one is started. Returns the backup path.
+pub fn reset_aliases() -> Result<PathBuf> {
+    let _lock = crate::lock::lock_config()?;
+    let alias_file = get_alias_file();
+    let backup = alias_file.with_extension("json.bak");
+    fs::rename(&alias_file, &backup)?;
+    Ok(backup)
+}
+
 pub fn get_alias(name: &str) -> Result<Option<String>> {
     let config = load_aliases()?;
     Ok(config.aliases.get(name).cloned())
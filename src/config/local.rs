@@ -1,38 +1,98 @@
 use crate::errors::{NodeError, Result};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-pub fn find_version_file() -> Option<(PathBuf, String)> {
-    let mut current_dir = std::env::current_dir().ok()?;
+/// A place a project can declare its Node.js version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VersionSource {
+    /// `.nvmrc`
+    Nvmrc,
+    /// `.node-version`, as used by fnm and nodenv
+    NodeVersion,
+    /// asdf/mise `.tool-versions`, e.g. `nodejs 20.11.1`
+    ToolVersions,
+    /// `devEngines.runtime` in `package.json`
+    DevEngines,
+    /// `engines.node` in `package.json`
+    Engines,
+    /// `volta.node` in `package.json`
+    Volta,
+}
 
-    loop {
-        let nvmrc = current_dir.join(".nvmrc");
-        if nvmrc.exists() {
-            if let Ok(version) = fs::read_to_string(&nvmrc) {
-                return Some((nvmrc, clean_version_string(version)));
-            }
+/// Sources checked in each directory, first match wins. Override with `version_files` in settings.json.
+pub const DEFAULT_VERSION_SOURCES: [VersionSource; 6] = [
+    VersionSource::Nvmrc,
+    VersionSource::NodeVersion,
+    VersionSource::ToolVersions,
+    VersionSource::DevEngines,
+    VersionSource::Volta,
+    VersionSource::Engines,
+];
+
+impl VersionSource {
+    fn file_name(&self) -> &'static str {
+        match self {
+            VersionSource::Nvmrc => ".nvmrc",
+            VersionSource::NodeVersion => ".node-version",
+            VersionSource::ToolVersions => ".tool-versions",
+            VersionSource::DevEngines | VersionSource::Engines | VersionSource::Volta => "package.json",
         }
+    }
+
+    fn read(&self, path: &Path) -> Option<String> {
+        let content = fs::read_to_string(path).ok()?;
+        let version = match self {
+            VersionSource::Nvmrc | VersionSource::NodeVersion => content,
+            VersionSource::ToolVersions => parse_tool_versions(&content)?,
+            VersionSource::DevEngines => parse_dev_engines(&serde_json::from_str(&content).ok()?)?,
+            VersionSource::Engines => package_json_field(&content, "engines")?,
+            VersionSource::Volta => resolve_volta_node(path)?,
+        };
+
+        let version = clean_version_string(version);
+        (!version.is_empty()).then_some(version)
+    }
+}
+
+impl std::fmt::Display for VersionSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionSource::DevEngines => write!(f, "package.json devEngines.runtime"),
+            VersionSource::Engines => write!(f, "package.json engines.node"),
+            VersionSource::Volta => write!(f, "package.json volta.node"),
+            source => write!(f, "{}", source.file_name()),
+        }
+    }
+}
+
+pub struct VersionFile {
+    pub path: PathBuf,
+    pub version: String,
+    pub source: VersionSource,
+}
 
-        let package_json = current_dir.join("package.json");
-        if package_json.exists() {
-            if let Ok(content) = fs::read_to_string(&package_json) {
-                if let Ok(json) = serde_json::from_str::<Value>(&content) {
-                    if let Some(engines) = json.get("engines") {
-                        if let Some(node_version) = engines.get("node") {
-                            if let Some(version) = node_version.as_str() {
-                                return Some((package_json, clean_version_string(version.to_string())));
-                            }
-                        }
-                    }
-                    if let Some(volta) = json.get("volta") {
-                        if let Some(node_version) = volta.get("node") {
-                            if let Some(version) = node_version.as_str() {
-                                return Some((package_json, clean_version_string(version.to_string())));
-                            }
-                        }
-                    }
-                }
+/// Finds the project version from the current directory upwards, using the configured source order.
+pub fn find_version_file() -> Option<VersionFile> {
+    let current_dir = std::env::current_dir().ok()?;
+    let sources = crate::config::settings::load_settings()
+        .ok()
+        .and_then(|settings| settings.version_files)
+        .unwrap_or_else(|| DEFAULT_VERSION_SOURCES.to_vec());
+    find_version_file_in(&current_dir, &sources)
+}
+
+/// The nearest directory with any of `sources` wins; within a directory, earlier sources win.
+pub fn find_version_file_in(start: &Path, sources: &[VersionSource]) -> Option<VersionFile> {
+    let mut current_dir = start.to_path_buf();
+
+    loop {
+        for source in sources {
+            let path = current_dir.join(source.file_name());
+            if let Some(version) = source.read(&path) {
+                return Some(VersionFile { path, version, source: *source });
             }
         }
 
@@ -44,6 +104,66 @@ pub fn find_version_file() -> Option<(PathBuf, String)> {
     None
 }
 
+fn package_json_field(content: &str, field: &str) -> Option<String> {
+    let json: Value = serde_json::from_str(content).ok()?;
+    json.get(field)?.get("node")?.as_str().map(str::to_string)
+}
+
+/// Reads `volta.node`, following `volta.extends` (relative to each file) until a file pins a version.
+fn resolve_volta_node(path: &Path) -> Option<String> {
+    let mut visited = Vec::new();
+    let mut path = path.to_path_buf();
+
+    loop {
+        let canonical = fs::canonicalize(&path).ok()?;
+        if visited.contains(&canonical) {
+            // Resolved on every shim call and `cd`, so this stays out of the way unless RUST_LOG asks.
+            log::debug!("volta.extends in {} forms a cycle", path.display());
+            return None;
+        }
+        visited.push(canonical);
+
+        let json: Value = serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+        let volta = json.get("volta")?;
+        if let Some(node) = volta.get("node").and_then(Value::as_str) {
+            return Some(node.to_string());
+        }
+
+        let extends = volta.get("extends")?.as_str()?;
+        path = path.parent()?.join(extends);
+    }
+}
+
+/// `devEngines.runtime` is a single `{ "name", "version" }` object or a list of them.
+fn parse_dev_engines(json: &Value) -> Option<String> {
+    let runtime = json.get("devEngines")?.get("runtime")?;
+    let runtimes = match runtime {
+        Value::Array(runtimes) => runtimes.iter().collect(),
+        runtime => vec![runtime],
+    };
+
+    runtimes
+        .into_iter()
+        .find(|runtime| runtime.get("name").and_then(Value::as_str) == Some("node"))?
+        .get("version")?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// Takes the first version on the `nodejs` (or mise's `node`) line; later ones are fallbacks.
+fn parse_tool_versions(content: &str) -> Option<String> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .find_map(|line| {
+            let mut fields = line.split_whitespace();
+            match fields.next()? {
+                "nodejs" | "node" => fields.next().map(str::to_string),
+                _ => None,
+            }
+        })
+}
+
 pub fn create_nvmrc(version: &str) -> Result<PathBuf> {
     let nvmrc_path = Path::new(".nvmrc");
     fs::write(nvmrc_path, version)?;
@@ -65,26 +185,101 @@ mod tests {
     use std::fs;
 
     #[test]
-    fn test_version_file_detection() {
+    fn test_version_sources() {
+        let cases = [
+            (".nvmrc", "v16.0.0\n", VersionSource::Nvmrc, "16.0.0"),
+            (".node-version", "20.11.1", VersionSource::NodeVersion, "20.11.1"),
+            (".tool-versions", "# pinned\npython 3.12.1\nnodejs 20.11.1 18.19.1\n", VersionSource::ToolVersions, "20.11.1"),
+            (".tool-versions", "node lts/iron", VersionSource::ToolVersions, "lts/iron"),
+            (
+                "package.json",
+                r#"{"devEngines": {"runtime": {"name": "node", "version": ">=20"}}}"#,
+                VersionSource::DevEngines,
+                ">=20",
+            ),
+            (
+                "package.json",
+                r#"{"devEngines": {"runtime": [{"name": "bun", "version": "1"}, {"name": "node", "version": "^22"}]}}"#,
+                VersionSource::DevEngines,
+                "^22",
+            ),
+            ("package.json", r#"{"engines": {"node": ">=14.0.0"}}"#, VersionSource::Engines, ">=14.0.0"),
+            ("package.json", r#"{"volta": {"node": "20.11.1"}}"#, VersionSource::Volta, "20.11.1"),
+        ];
+
+        for (file, content, source, version) in cases {
+            let temp_dir = tempfile::tempdir().unwrap();
+            fs::write(temp_dir.path().join(file), content).unwrap();
+
+            let found = find_version_file_in(temp_dir.path(), &DEFAULT_VERSION_SOURCES).unwrap();
+            assert_eq!(found.source, source, "{}", content);
+            assert_eq!(found.version, version);
+            assert_eq!(found.path, temp_dir.path().join(file));
+        }
+    }
+
+    #[test]
+    fn test_version_source_precedence() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let temp_path = temp_dir.path();
-        std::env::set_current_dir(temp_path).unwrap();
-
-        fs::write(".nvmrc", "16.0.0").unwrap();
-        let (path, version) = find_version_file().unwrap();
-        assert_eq!(path.file_name().unwrap(), ".nvmrc");
-        assert_eq!(version, "16.0.0");
-
-        // Test package.json detection
-        let package_json = r#"{
-            "engines": {
-                "node": ">=14.0.0"
-            }
-        }"#;
-        fs::write("package.json", package_json).unwrap();
-        let (path, version) = find_version_file().unwrap();
-        assert_eq!(path.file_name().unwrap(), "package.json");
-        assert_eq!(version, ">=14.0.0");
+        let project = temp_dir.path().join("packages").join("app");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(temp_dir.path().join(".nvmrc"), "18").unwrap();
+        fs::write(project.join(".tool-versions"), "ruby 3.3.0").unwrap();
+
+        // Unrelated .tool-versions entries are skipped and the search continues upwards.
+        let found = find_version_file_in(&project, &DEFAULT_VERSION_SOURCES).unwrap();
+        assert_eq!((found.source, found.version.as_str()), (VersionSource::Nvmrc, "18"));
+
+        fs::write(project.join(".node-version"), "20").unwrap();
+        fs::write(project.join("package.json"), r#"{"engines": {"node": ">=22"}}"#).unwrap();
+        let found = find_version_file_in(&project, &DEFAULT_VERSION_SOURCES).unwrap();
+        assert_eq!((found.source, found.version.as_str()), (VersionSource::NodeVersion, "20"));
+
+        let found = find_version_file_in(&project, &[VersionSource::Engines, VersionSource::NodeVersion]).unwrap();
+        assert_eq!((found.source, found.version.as_str()), (VersionSource::Engines, ">=22"));
+
+        assert!(find_version_file_in(&project, &[VersionSource::Volta]).is_none());
+
+        // Volta's exact pin beats the engines range in the same package.json.
+        fs::remove_file(project.join(".node-version")).unwrap();
+        fs::write(project.join("package.json"), r#"{"engines": {"node": ">=18"}, "volta": {"node": "20.11.1"}}"#).unwrap();
+        let found = find_version_file_in(&project, &DEFAULT_VERSION_SOURCES).unwrap();
+        assert_eq!((found.source, found.version.as_str()), (VersionSource::Volta, "20.11.1"));
+    }
+
+    #[test]
+    fn test_volta_extends() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        let app = root.join("packages").join("app");
+        let lib = root.join("packages").join("lib");
+        fs::create_dir_all(&app).unwrap();
+        fs::create_dir_all(&lib).unwrap();
+
+        fs::write(root.join("package.json"), r#"{"volta": {"node": "20.11.1"}}"#).unwrap();
+        fs::write(root.join("packages").join("base.json"), r#"{"volta": {"extends": "../package.json"}}"#).unwrap();
+        fs::write(app.join("package.json"), r#"{"volta": {"extends": "../base.json"}}"#).unwrap();
+        fs::write(lib.join("package.json"), r#"{"volta": {"node": "18.19.1", "extends": "../../package.json"}}"#).unwrap();
+
+        let found = find_version_file_in(&app, &DEFAULT_VERSION_SOURCES).unwrap();
+        assert_eq!((found.source, found.version.as_str()), (VersionSource::Volta, "20.11.1"));
+        assert_eq!(found.path, app.join("package.json"));
+
+        // A package's own pin wins over the one it extends.
+        let found = find_version_file_in(&lib, &DEFAULT_VERSION_SOURCES).unwrap();
+        assert_eq!(found.version, "18.19.1");
+
+        fs::write(root.join("package.json"), r#"{"volta": {"extends": "packages/app/package.json"}}"#).unwrap();
+        assert!(resolve_volta_node(&app.join("package.json")).is_none());
+    }
+
+    #[test]
+    fn test_version_sources_setting() {
+        let sources: Vec<VersionSource> = serde_json::from_str(r#"["tool-versions", "nvmrc", "dev-engines"]"#).unwrap();
+        assert_eq!(
+            sources,
+            vec![VersionSource::ToolVersions, VersionSource::Nvmrc, VersionSource::DevEngines]
+        );
     }
 
     #[test]
@@ -1,5 +1,6 @@
 pub mod alias;
 pub mod local;
+pub mod settings;
 
 use std::path::PathBuf;
 
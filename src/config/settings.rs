@@ -0,0 +1,122 @@
+use crate::config::local::VersionSource;
+use crate::errors::{NodeError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const DEFAULT_LOCK_TIMEOUT_SECS: u64 = 10 * 60;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub verify_signature: bool,
+    pub release_keyring: Option<PathBuf>,
+    pub mirror: Option<String>,
+    pub index_cache_ttl: Option<u64>,
+    pub version_files: Option<Vec<VersionSource>>,
+    pub lock_timeout: Option<u64>,
+}
+
+/// Flags given on the command line. They win over the environment and settings.json, and unlike
+/// environment variables they are not inherited by the commands rnvm runs.
+#[derive(Debug, Default)]
+pub struct CliOverrides {
+    pub verify_signature: bool,
+    pub mirror: Option<String>,
+    pub offline: bool,
+}
+
+static CLI_OVERRIDES: OnceLock<CliOverrides> = OnceLock::new();
+
+pub fn set_cli_overrides(overrides: CliOverrides) {
+    let _ = CLI_OVERRIDES.set(overrides);
+}
+
+pub fn cli_overrides() -> &'static CliOverrides {
+    CLI_OVERRIDES.get_or_init(CliOverrides::default)
+}
+
+fn get_settings_file() -> PathBuf {
+    crate::config::get_config_dir().join("settings.json")
+}
+
+pub fn load_settings() -> Result<Settings> {
+    let settings_file = get_settings_file();
+    if !settings_file.exists() {
+        return Ok(Settings::default());
+    }
+
+    let content = fs::read_to_string(&settings_file)?;
+    serde_json::from_str(&content)
+        .map_err(|e| NodeError::ConfigError(format!("Failed to parse settings file: {}", e)))
+}
+
+/// Reads a boolean switch from the environment, accepting `1`/`true`/`yes` and `0`/`false`/`no`.
+pub fn env_flag(name: &str) -> Option<bool> {
+    let value = std::env::var(name).ok()?;
+    match value.trim().to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" | "" => Some(false),
+        _ => None,
+    }
+}
+
+pub fn verify_signature() -> Result<bool> {
+    if cli_overrides().verify_signature {
+        return Ok(true);
+    }
+    if let Some(enabled) = env_flag("RNVM_VERIFY_SIGNATURE") {
+        return Ok(enabled);
+    }
+    Ok(load_settings()?.verify_signature)
+}
+
+pub fn offline() -> bool {
+    cli_overrides().offline || env_flag("RNVM_OFFLINE").unwrap_or(false)
+}
+
+/// How long to wait for another rnvm process to release a lock: `RNVM_LOCK_TIMEOUT` (seconds),
+/// then `lock_timeout` in settings.json.
+pub fn lock_timeout() -> Result<Duration> {
+    if let Ok(value) = std::env::var("RNVM_LOCK_TIMEOUT") {
+        let secs = value
+            .trim()
+            .parse()
+            .map_err(|_| NodeError::ConfigError(format!("Invalid RNVM_LOCK_TIMEOUT: {}", value)))?;
+        return Ok(Duration::from_secs(secs));
+    }
+    Ok(Duration::from_secs(
+        load_settings()?.lock_timeout.unwrap_or(DEFAULT_LOCK_TIMEOUT_SECS),
+    ))
+}
+
+/// A keyring chosen with `RNVM_RELEASE_KEYRING` or `release_keyring` in settings.json.
+pub fn configured_release_keyring() -> Result<Option<PathBuf>> {
+    if let Ok(path) = std::env::var("RNVM_RELEASE_KEYRING") {
+        return Ok(Some(PathBuf::from(path)));
+    }
+    Ok(load_settings()?.release_keyring)
+}
+
+/// Where `rnvm keys update` saves the release keys: the configured keyring, or `~/.rnvm/keys`.
+pub fn release_keyring() -> Result<PathBuf> {
+    Ok(configured_release_keyring()?
+        .unwrap_or_else(|| crate::utils::get_base_dir().join("keys").join("nodejs-release-keys.asc")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_defaults() {
+        let settings: Settings = serde_json::from_str("{}").unwrap();
+        assert!(!settings.verify_signature);
+        assert!(settings.release_keyring.is_none());
+
+        let settings: Settings = serde_json::from_str(r#"{"verify_signature": true}"#).unwrap();
+        assert!(settings.verify_signature);
+    }
+}
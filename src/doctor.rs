@@ -0,0 +1,269 @@
+use serde::Serialize;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub message: String,
+    pub hint: Option<String>,
+    /// Set when `--fix` repaired the problem; `status` then reflects the state after the fix.
+    pub fixed: bool,
+}
+
+impl Check {
+    fn pass(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Pass,
+            message: message.into(),
+            hint: None,
+            fixed: false,
+        }
+    }
+
+    fn warn(name: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            status: Status::Warn,
+            hint: Some(hint.into()),
+            ..Self::pass(name, message)
+        }
+    }
+
+    fn fail(name: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            status: Status::Fail,
+            ..Self::warn(name, message, hint)
+        }
+    }
+
+    fn fixed(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            fixed: true,
+            ..Self::pass(name, message)
+        }
+    }
+}
+
+/// Runs every check. With `fix`, problems that can be repaired without losing data are repaired.
+pub fn run_checks(fix: bool) -> Vec<Check> {
+    vec![
+        check_current_link(fix),
+        check_path_order(),
+        check_nvm(),
+        check_aliases(fix),
+        check_versions_dir(fix),
+        check_installs(fix),
+    ]
+}
+
+fn check_current_link(fix: bool) -> Check {
+    const NAME: &str = "current";
+    let local = crate::version::local::get_current_link();
+    let global = crate::version::local::get_global_link();
+    let links = if local == global { vec![global] } else { vec![local, global] };
+
+    for link in links {
+        let Ok(target) = fs::read_link(&link) else {
+            continue;
+        };
+        if target.exists() {
+            continue;
+        }
+
+        let message = format!("{} points to {}, which no longer exists", link.display(), target.display());
+        if !fix {
+            return Check::fail(NAME, message, "Run 'rnvm use <version> --default' or 'rnvm doctor --fix'");
+        }
+
+        // Hold the config lock like `use` and `alias` do, so the link is not switched under us.
+        let _lock = match crate::lock::lock_config() {
+            Ok(lock) => lock,
+            Err(e) => return Check::fail(NAME, format!("{} ({})", message, e), "Try again once other rnvm commands finish"),
+        };
+        if let Err(e) = crate::version::local::remove_link(&link) {
+            return Check::fail(NAME, format!("{} ({})", message, e), format!("Remove {} by hand", link.display()));
+        }
+        let default = crate::config::alias::get_alias("default")
+            .ok()
+            .flatten()
+            .and_then(|spec| crate::version::find_installed_version(&spec).ok().flatten());
+        return match default {
+            Some(version) if crate::version::local::replace_link(&version_path(&version.version_str()), &link).is_ok() => {
+                Check::fixed(NAME, format!("Pointed {} at the default version {}", link.display(), version.version_str()))
+            }
+            _ => Check::fixed(NAME, format!("Removed dangling link {}", link.display())),
+        };
+    }
+
+    match crate::version::local::get_current_version() {
+        Ok(Some(version)) => Check::pass(NAME, format!("Using Node.js {}", version)),
+        _ => Check::pass(NAME, "No version selected"),
+    }
+}
+
+fn version_path(version: &str) -> PathBuf {
+    crate::version::local::get_versions_dir().join(version)
+}
+
+fn check_path_order() -> Check {
+    const NAME: &str = "path";
+    let hint = "Add 'eval \"$(rnvm env)\"' at the end of your shell config, or put ~/.rnvm/shims first on PATH";
+    let path = std::env::var_os("PATH").unwrap_or_default();
+
+    match find_on_path(if cfg!(windows) { "node.exe" } else { "node" }, &path) {
+        Some(node) if node.starts_with(crate::utils::get_base_dir()) => {
+            Check::pass(NAME, format!("node resolves to {}", node.display()))
+        }
+        Some(node) => Check::warn(NAME, format!("{} comes before rnvm on PATH", node.display()), hint),
+        None => Check::warn(NAME, "No node found on PATH", hint),
+    }
+}
+
+fn find_on_path(program: &str, path: &OsStr) -> Option<PathBuf> {
+    std::env::split_paths(path)
+        .map(|dir| dir.join(program))
+        .find(|candidate| candidate.is_file())
+}
+
+fn check_nvm() -> Check {
+    const NAME: &str = "nvm";
+    let home = crate::utils::get_home_dir();
+
+    let sourcing: Vec<String> = [".bashrc", ".bash_profile", ".profile", ".zshrc", ".zprofile", ".config/fish/config.fish"]
+        .iter()
+        .map(|file| home.join(file))
+        .filter(|file| fs::read_to_string(file).is_ok_and(|content| sources_nvm(&content)))
+        .map(|file| file.display().to_string())
+        .collect();
+
+    if !sourcing.is_empty() {
+        return Check::warn(
+            NAME,
+            format!("nvm is still loaded from {}", sourcing.join(", ")),
+            "Remove the nvm.sh lines; nvm rewrites PATH and shadows rnvm",
+        );
+    }
+    if std::env::var_os("NVM_DIR").is_some() {
+        return Check::warn(NAME, "NVM_DIR is set in this shell", "Open a new shell after removing nvm from your config");
+    }
+    Check::pass(NAME, "nvm is not loaded")
+}
+
+fn sources_nvm(content: &str) -> bool {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .any(|line| line.contains("nvm.sh"))
+}
+
+fn check_aliases(fix: bool) -> Check {
+    const NAME: &str = "aliases";
+    match crate::config::alias::list_aliases() {
+        Ok(aliases) => Check::pass(NAME, format!("{} aliases", aliases.len())),
+        Err(e) if fix => match crate::config::alias::reset_aliases() {
+            Ok(backup) => Check::fixed(NAME, format!("Moved the unreadable alias file to {}", backup.display())),
+            Err(reset_error) => Check::fail(NAME, format!("{} ({})", e, reset_error), "Delete ~/.rnvm/config/aliases.json"),
+        },
+        Err(e) => Check::fail(
+            NAME,
+            e.to_string(),
+            "Fix ~/.rnvm/config/aliases.json by hand, or run 'rnvm doctor --fix' to back it up and start over",
+        ),
+    }
+}
+
+fn check_versions_dir(fix: bool) -> Check {
+    const NAME: &str = "permissions";
+    let versions_dir = crate::version::local::get_versions_dir();
+
+    let mut created = false;
+    if !versions_dir.exists() {
+        if !fix {
+            return Check::warn(
+                NAME,
+                format!("{} does not exist", versions_dir.display()),
+                "It is created on the first install, or run 'rnvm doctor --fix'",
+            );
+        }
+        if let Err(e) = fs::create_dir_all(&versions_dir) {
+            return Check::fail(NAME, format!("Cannot create {}: {}", versions_dir.display(), e), "Check the permissions of ~/.rnvm");
+        }
+        created = true;
+    }
+
+    match crate::utils::check_permissions(&versions_dir) {
+        Ok(()) if created => Check::fixed(NAME, format!("Created {}", versions_dir.display())),
+        Ok(()) => Check::pass(NAME, format!("{} is writable", versions_dir.display())),
+        Err(e) => Check::fail(NAME, e.to_string(), format!("Make {} writable by your user", versions_dir.display())),
+    }
+}
+
+/// With `fix`, installs made by an rnvm that predates install manifests are adopted. Other broken
+/// installs are only reported: reinstalling downloads whole releases, so it is left to
+/// `rnvm install --repair`.
+fn check_installs(fix: bool) -> Check {
+    const NAME: &str = "installs";
+    let adopted = if fix {
+        match crate::version::local::adopt_legacy_versions() {
+            Ok(adopted) => adopted,
+            Err(e) => return Check::fail(NAME, e.to_string(), "Check the permissions of ~/.rnvm/versions"),
+        }
+    } else {
+        Vec::new()
+    };
+
+    match crate::version::local::get_broken_versions() {
+        Ok(broken) if broken.is_empty() && adopted.is_empty() => Check::pass(NAME, "All installs are complete"),
+        Ok(broken) if broken.is_empty() => Check::fixed(
+            NAME,
+            format!("Adopted installs from an earlier rnvm: {}", adopted.join(", ")),
+        ),
+        Ok(broken) => Check::warn(
+            NAME,
+            if adopted.is_empty() {
+                format!("Incomplete installs: {}", broken.join(", "))
+            } else {
+                format!("Incomplete installs: {} (adopted {})", broken.join(", "), adopted.join(", "))
+            },
+            "Run 'rnvm install --repair' to reinstall them. Installs from an earlier rnvm are adopted by 'rnvm doctor --fix'",
+        ),
+        Err(e) => Check::fail(NAME, e.to_string(), "Check the permissions of ~/.rnvm/versions"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sources_nvm() {
+        assert!(sources_nvm("export NVM_DIR=\"$HOME/.nvm\"\n[ -s \"$NVM_DIR/nvm.sh\" ] && \\. \"$NVM_DIR/nvm.sh\"\n"));
+        assert!(!sources_nvm("# . ~/.nvm/nvm.sh\neval \"$(rnvm env)\"\n"));
+    }
+
+    #[test]
+    fn test_find_on_path() {
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        fs::write(second.path().join("node"), "").unwrap();
+
+        let path = std::env::join_paths([first.path(), second.path()]).unwrap();
+        assert_eq!(find_on_path("node", &path), Some(second.path().join("node")));
+
+        fs::write(first.path().join("node"), "").unwrap();
+        assert_eq!(find_on_path("node", &path), Some(first.path().join("node")));
+        assert_eq!(find_on_path("npm", &path), None);
+    }
+}
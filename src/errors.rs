@@ -12,12 +12,21 @@ pub enum NodeError {
     #[error("Download error: {0}")]
     DownloadError(String),
 
+    #[error("Offline mode: {0}")]
+    OfflineError(String),
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 
     #[error("Invalid version format: {0}")]
     InvalidVersion(String),
 
+    #[error("Checksum verification failed: {0}")]
+    ChecksumError(String),
+
+    #[error("Signature verification failed: {0}")]
+    SignatureError(String),
+
     #[error("Extraction error: {0}")]
     ExtractionError(String),
 
@@ -27,6 +36,9 @@ pub enum NodeError {
     #[error("Alias error: {0}")]
     AliasError(String),
 
+    #[error("Lock error: {0}")]
+    LockError(String),
+
     #[error("System error: {0}")]
     SystemError(String),
 
@@ -0,0 +1,61 @@
+use crate::errors::{NodeError, Result};
+use std::ffi::{OsStr, OsString};
+use std::path::Path;
+use std::process::Command;
+
+/// Runs `program` with `version`'s `bin` directory first on PATH, without switching versions.
+///
+/// On Unix the current process is replaced, so signals reach the child directly and its
+/// exit status becomes ours. Elsewhere the child is awaited and its exit code forwarded.
+pub fn run_with_version<I, S>(version: &str, program: &str, args: I) -> Result<()>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let version_dir = crate::version::local::get_versions_dir().join(version);
+    if !crate::version::local::is_installed(version)? {
+        return Err(NodeError::VersionNotInstalled(version.to_string()));
+    }
+
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .env("PATH", path_with(&crate::version::local::get_bin_dir(&version_dir))?);
+
+    spawn(command, program)
+}
+
+fn path_with(bin_dir: &Path) -> Result<OsString> {
+    let current = std::env::var_os("PATH").unwrap_or_default();
+    let paths = std::iter::once(bin_dir.to_path_buf()).chain(std::env::split_paths(&current));
+    std::env::join_paths(paths).map_err(|e| NodeError::SystemError(format!("Invalid PATH: {}", e)))
+}
+
+#[cfg(unix)]
+fn spawn(mut command: Command, program: &str) -> Result<()> {
+    use std::os::unix::process::CommandExt;
+
+    let error = command.exec();
+    Err(NodeError::SystemError(format!("Failed to run {}: {}", program, error)))
+}
+
+#[cfg(not(unix))]
+fn spawn(mut command: Command, program: &str) -> Result<()> {
+    let status = command
+        .status()
+        .map_err(|e| NodeError::SystemError(format!("Failed to run {}: {}", program, e)))?;
+    std::process::exit(status.code().unwrap_or(1));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_with_prepends_bin_dir() {
+        let bin_dir = Path::new("/home/user/.rnvm/versions/18.19.1/bin");
+        let path = path_with(bin_dir).unwrap();
+        let first = std::env::split_paths(&path).next().unwrap();
+        assert_eq!(first, bin_dir);
+    }
+}
@@ -0,0 +1,284 @@
+use crate::errors::{NodeError, Result};
+use clap::ValueEnum;
+use semver::Version;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImportSource {
+    Nvm,
+    Fnm,
+    N,
+    Volta,
+}
+
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub versions: Vec<String>,
+    pub aliases: Vec<(String, String)>,
+    /// What was left out, with the reason.
+    pub skipped: Vec<(String, String)>,
+}
+
+impl ImportSource {
+    fn name(&self) -> &'static str {
+        match self {
+            ImportSource::Nvm => "nvm",
+            ImportSource::Fnm => "fnm",
+            ImportSource::N => "n",
+            ImportSource::Volta => "Volta",
+        }
+    }
+
+    /// Directory holding one subdirectory per installed version.
+    fn versions_dir(&self, home: &Path) -> Option<PathBuf> {
+        let env_dir = |name: &str| std::env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+        let candidates = match self {
+            ImportSource::Nvm => vec![env_dir("NVM_DIR").unwrap_or_else(|| home.join(".nvm")).join("versions").join("node")],
+            ImportSource::Fnm => {
+                let mut dirs: Vec<PathBuf> = env_dir("FNM_DIR").into_iter().collect();
+                dirs.extend(env_dir("XDG_DATA_HOME").map(|dir| dir.join("fnm")));
+                dirs.push(home.join(".local").join("share").join("fnm"));
+                dirs.push(home.join("Library").join("Application Support").join("fnm"));
+                dirs.push(home.join(".fnm"));
+                dirs.into_iter().map(|dir| dir.join("node-versions")).collect()
+            }
+            ImportSource::N => vec![env_dir("N_PREFIX")
+                .unwrap_or_else(|| PathBuf::from("/usr/local"))
+                .join("n")
+                .join("versions")
+                .join("node")],
+            ImportSource::Volta => vec![env_dir("VOLTA_HOME")
+                .unwrap_or_else(|| home.join(".volta"))
+                .join("tools")
+                .join("image")
+                .join("node")],
+        };
+        candidates.into_iter().find(|dir| dir.is_dir())
+    }
+
+    /// Where the Node.js tree lives inside a version's directory.
+    fn install_dir(&self, version_dir: &Path) -> PathBuf {
+        match self {
+            ImportSource::Fnm => version_dir.join("installation"),
+            _ => version_dir.to_path_buf(),
+        }
+    }
+}
+
+/// Imports every version installed by `source`, plus nvm's aliases. Versions rnvm already has
+/// are left alone. Files are copied, or hardlinked where possible when `hardlink` is set.
+pub fn import_from(source: ImportSource, hardlink: bool) -> Result<ImportReport> {
+    let versions_dir = source
+        .versions_dir(&crate::utils::get_home_dir())
+        .ok_or_else(|| NodeError::ConfigError(format!("No {} installation found", source.name())))?;
+
+    let mut report = ImportReport::default();
+    for (name, version) in find_installs(&versions_dir) {
+        let Some(version) = version else {
+            report.skipped.push((name, "not a Node.js version".to_string()));
+            continue;
+        };
+        let version = version.to_string();
+        let install_dir = source.install_dir(&versions_dir.join(&name));
+        if !crate::version::local::get_node_binary(&install_dir).is_file() {
+            report.skipped.push((version, "incomplete install, no node binary".to_string()));
+            continue;
+        }
+
+        match import_version(&install_dir, &version, hardlink) {
+            Ok(true) => report.versions.push(version),
+            Ok(false) => report.skipped.push((version, "already installed".to_string())),
+            Err(e) => report.skipped.push((version, e.to_string())),
+        }
+    }
+
+    if source == ImportSource::Nvm {
+        let alias_dir = versions_dir.parent().and_then(Path::parent).map(|nvm_dir| nvm_dir.join("alias"));
+        if let Some(alias_dir) = alias_dir {
+            import_nvm_aliases(&alias_dir, &mut report)?;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Version dirs under `versions_dir`, sorted by name, with the parsed version when the name is one.
+fn find_installs(versions_dir: &Path) -> Vec<(String, Option<Version>)> {
+    let Ok(entries) = fs::read_dir(versions_dir) else {
+        return Vec::new();
+    };
+
+    let mut installs: Vec<_> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .map(|name| {
+            let version = Version::parse(name.trim_start_matches('v')).ok();
+            (name, version)
+        })
+        .collect();
+    installs.sort();
+    installs
+}
+
+/// Returns `Ok(false)` when the version is already installed.
+fn import_version(install_dir: &Path, version: &str, hardlink: bool) -> Result<bool> {
+    if crate::version::local::is_installed(version)? {
+        return Ok(false);
+    }
+
+    let _lock = crate::lock::lock_version(version)?;
+    if crate::version::local::is_installed(version)? {
+        return Ok(false);
+    }
+
+    let staging = crate::installer::extract::create_staging_dir()?;
+    let staged_dir = staging.path().join(version);
+    copy_tree(install_dir, &staged_dir, hardlink)?;
+
+    let source_url = format!("file://{}", install_dir.display());
+    crate::installer::extract::finish_install(&staged_dir, version, &source_url, None)?;
+    Ok(true)
+}
+
+/// Hardlinked files share their inode with the source tree, so a write through either one
+/// shows up in both; that is why hardlinking is opt-in.
+fn copy_tree(from: &Path, to: &Path, hardlink: bool) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let target = to.join(entry.file_name());
+
+        if file_type.is_dir() {
+            copy_tree(&entry.path(), &target, hardlink)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&entry.path(), &target)?;
+        } else if !hardlink || fs::hard_link(entry.path(), &target).is_err() {
+            // Hardlinks fail across filesystems; fall back to a real copy.
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> Result<()> {
+    // npm and npx are relative links into lib/, so the same target works in the new tree.
+    std::os::unix::fs::symlink(fs::read_link(from)?, to)?;
+    Ok(())
+}
+
+#[cfg(windows)]
+fn copy_symlink(from: &Path, to: &Path) -> Result<()> {
+    // fs::copy cannot copy a directory, so directory links are recreated instead.
+    if fs::metadata(from)?.is_dir() {
+        std::os::windows::fs::symlink_dir(fs::read_link(from)?, to)?;
+    } else {
+        fs::copy(from, to)?;
+    }
+    Ok(())
+}
+
+/// Each file in nvm's `alias/` dir is one alias; `alias/lts/` holds nvm's own LTS bookkeeping.
+fn import_nvm_aliases(alias_dir: &Path, report: &mut ImportReport) -> Result<()> {
+    let Ok(entries) = fs::read_dir(alias_dir) else {
+        return Ok(());
+    };
+    let existing = crate::config::alias::list_aliases()?;
+
+    let mut aliases: Vec<(String, String)> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let value = fs::read_to_string(entry.path()).ok()?;
+            Some((name, value.trim().to_string()))
+        })
+        .collect();
+    aliases.sort();
+
+    for (name, value) in aliases {
+        let alias = format!("alias {}", name);
+        if existing.contains_key(&name) {
+            report.skipped.push((alias, "an rnvm alias with that name exists".to_string()));
+            continue;
+        }
+        match translate_nvm_alias(&value) {
+            Some(version) => {
+                crate::config::alias::set_alias(&name, &version)?;
+                report.aliases.push((name, version));
+            }
+            None => report.skipped.push((alias, format!("'{}' has no rnvm equivalent", value))),
+        }
+    }
+    Ok(())
+}
+
+/// Maps an nvm alias target onto the spec rnvm understands for the same thing.
+fn translate_nvm_alias(value: &str) -> Option<String> {
+    match value {
+        "" | "system" | "iojs" => None,
+        "lts/*" => Some("lts".to_string()),
+        "stable" | "node" => Some("latest".to_string()),
+        value if value.starts_with("iojs") => None,
+        value => Some(value.trim_start_matches('v').to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate_nvm_alias() {
+        let cases = [
+            ("v20.11.1", Some("20.11.1")),
+            ("18", Some("18")),
+            ("lts/*", Some("lts")),
+            ("lts/iron", Some("lts/iron")),
+            ("node", Some("latest")),
+            ("system", None),
+            ("iojs-v3.3.1", None),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(translate_nvm_alias(value).as_deref(), expected, "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_find_installs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        for name in ["v20.11.1", "18.19.1", "iojs-v3.3.1"] {
+            fs::create_dir(temp_dir.path().join(name)).unwrap();
+        }
+        fs::write(temp_dir.path().join(".DS_Store"), "").unwrap();
+
+        let installs = find_installs(temp_dir.path());
+        let names: Vec<_> = installs.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["18.19.1", "iojs-v3.3.1", "v20.11.1"]);
+        assert_eq!(installs[1].1, None);
+        assert_eq!(installs[2].1, Some(Version::new(20, 11, 1)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_tree() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let from = temp_dir.path().join("from");
+        fs::create_dir_all(from.join("bin")).unwrap();
+        fs::create_dir_all(from.join("lib")).unwrap();
+        fs::write(from.join("bin").join("node"), "node").unwrap();
+        fs::write(from.join("lib").join("npm-cli.js"), "npm").unwrap();
+        std::os::unix::fs::symlink("../lib/npm-cli.js", from.join("bin").join("npm")).unwrap();
+
+        for hardlink in [true, false] {
+            let to = temp_dir.path().join(format!("to-{}", hardlink));
+            copy_tree(&from, &to, hardlink).unwrap();
+            assert_eq!(fs::read_to_string(to.join("bin").join("node")).unwrap(), "node");
+            assert_eq!(fs::read_link(to.join("bin").join("npm")).unwrap(), Path::new("../lib/npm-cli.js"));
+            assert_eq!(fs::read_to_string(to.join("bin").join("npm")).unwrap(), "npm");
+        }
+    }
+}
@@ -2,45 +2,94 @@ use crate::errors::{NodeError, Result};
 use crate::version::NodeVersion;
 use futures_util::StreamExt;
 use indicatif::{ProgressBar, ProgressStyle};
-use reqwest::Client;
-use std::fs::File;
-use std::io::Write;
-use std::path::PathBuf;
-pub async fn download_version(version: &NodeVersion, download_dir: &PathBuf) -> Result<PathBuf> {
+use reqwest::header::{HeaderMap, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+pub struct DownloadedArchive {
+    pub path: PathBuf,
+    pub url: String,
+    pub sha256: String,
+}
+
+impl DownloadedArchive {
+    pub fn filename(&self) -> &str {
+        self.path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default()
+    }
+}
+
+/// Validators recorded next to a `.partial` file so an interrupted download can be resumed.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct PartialState {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl PartialState {
+    fn from_headers(url: &str, headers: &HeaderMap) -> Self {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string())
+        };
+        Self {
+            url: url.to_string(),
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        }
+    }
+
+    /// Value for `If-Range`. Weak ETags are not allowed there, so fall back to Last-Modified.
+    fn if_range(&self) -> Option<&str> {
+        self.etag
+            .as_deref()
+            .filter(|etag| !etag.starts_with("W/"))
+            .or(self.last_modified.as_deref())
+    }
+
+    fn load(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| NodeError::DownloadError(format!("Failed to record download state: {}", e)))?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+pub async fn download_version(version: &NodeVersion, download_dir: &Path) -> Result<DownloadedArchive> {
     let client = Client::new();
-    let url = crate::version::remote::get_download_url(version);
+    let url = crate::version::remote::get_download_url(version)?;
 
     let filename = url
         .split('/')
-        .last()
+        .next_back()
         .ok_or_else(|| NodeError::DownloadError("Invalid URL".to_string()))?;
     let output_path = download_dir.join(filename);
+    let partial_path = download_dir.join(format!("{}.partial", filename));
+    let state_path = download_dir.join(format!("{}.partial.json", filename));
 
     println!("Downloading Node.js {} from {}", version.version_str(), url);
 
-    let response = client
-        .head(&url)
-        .send()
-        .await
-        .map_err(|e| NodeError::DownloadError(e.to_string()))?;
-    let total_size = response
-        .headers()
-        .get("content-length")
-        .and_then(|ct_len| ct_len.to_str().ok())
-        .and_then(|ct_len| ct_len.parse().ok())
-        .unwrap_or(0);
-
-    let pb = ProgressBar::new(total_size);
-    pb.set_style(ProgressStyle::default_bar()
-        .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")
-        .unwrap()
-        .progress_chars("#>-"));
+    let mut resume_from = resumable_length(&partial_path, &state_path, &url);
+    let mut response = send_download_request(&client, &url, resume_from.as_ref()).await?;
 
-    let response = client
-        .get(&url)
-        .send()
-        .await
-        .map_err(|e| NodeError::DownloadError(e.to_string()))?;
+    if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        resume_from = None;
+        response = send_download_request(&client, &url, None).await?;
+    }
 
     if !response.status().is_success() {
         return Err(NodeError::DownloadError(format!(
@@ -49,17 +98,139 @@ pub async fn download_version(version: &NodeVersion, download_dir: &PathBuf) ->
         )));
     }
 
-    let mut file = File::create(&output_path)?;
-    let mut downloaded: u64 = 0;
-    let mut bytes = response
-        .bytes()
-        .await
-        .map_err(|e| NodeError::DownloadError(e.to_string()))?;
-    file.write_all(&bytes)?;
-    downloaded += bytes.len() as u64;
+    let mut hasher = Sha256::new();
+    let (mut file, downloaded) = match resume_from {
+        Some((offset, _)) if response.status() == StatusCode::PARTIAL_CONTENT => {
+            println!("Resuming download at {} bytes", offset);
+            hash_file(&partial_path, &mut hasher)?;
+            (OpenOptions::new().append(true).open(&partial_path)?, offset)
+        }
+        _ => {
+            // Either a fresh download or the server ignored the range because the file changed.
+            PartialState::from_headers(&url, response.headers()).save(&state_path)?;
+            (File::create(&partial_path)?, 0)
+        }
+    };
+
+    let pb = ProgressBar::new(downloaded + response.content_length().unwrap_or(0));
+    pb.set_style(ProgressStyle::default_bar()
+        .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")
+        .unwrap()
+        .progress_chars("#>-"));
     pb.set_position(downloaded);
 
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| {
+            NodeError::DownloadError(format!("{}. Run the command again to resume the download.", e))
+        })?;
+        file.write_all(&chunk)?;
+        hasher.update(&chunk);
+        pb.inc(chunk.len() as u64);
+    }
+    file.flush()?;
+    drop(file);
+
+    fs::rename(&partial_path, &output_path)?;
+    let _ = fs::remove_file(&state_path);
     pb.finish_with_message("Download completed");
 
-    Ok(output_path)
+    Ok(DownloadedArchive {
+        path: output_path,
+        url,
+        sha256: format!("{:x}", hasher.finalize()),
+    })
+}
+
+/// Returns the size of a previous partial download and the validator to resume it with.
+fn resumable_length(partial_path: &Path, state_path: &Path, url: &str) -> Option<(u64, String)> {
+    let state = PartialState::load(state_path).filter(|s| s.url == url)?;
+    let validator = state.if_range()?.to_string();
+    let len = fs::metadata(partial_path).ok()?.len();
+    (len > 0).then_some((len, validator))
+}
+
+async fn send_download_request(
+    client: &Client,
+    url: &str,
+    resume_from: Option<&(u64, String)>,
+) -> Result<reqwest::Response> {
+    let dist_url = crate::version::remote::get_dist_url()?;
+    let mut request = crate::version::remote::authorize(client.get(url), &dist_url);
+    if let Some((offset, validator)) = resume_from {
+        request = request
+            .header(RANGE, format!("bytes={}-", offset))
+            .header(IF_RANGE, validator);
+    }
+
+    request
+        .send()
+        .await
+        .map_err(|e| NodeError::DownloadError(e.to_string()))
+}
+
+fn hash_file(path: &Path, hasher: &mut Sha256) -> Result<()> {
+    let mut file = File::open(path)?;
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "https://nodejs.org/dist/v20.11.1/node-v20.11.1-linux-x64.tar.gz";
+
+    #[test]
+    fn test_if_range_prefers_strong_etag() {
+        let state = PartialState {
+            url: URL.to_string(),
+            etag: Some("\"abc\"".to_string()),
+            last_modified: Some("Tue, 13 Feb 2024 22:00:00 GMT".to_string()),
+        };
+        assert_eq!(state.if_range(), Some("\"abc\""));
+
+        let weak = PartialState {
+            etag: Some("W/\"abc\"".to_string()),
+            ..state
+        };
+        assert_eq!(weak.if_range(), Some("Tue, 13 Feb 2024 22:00:00 GMT"));
+
+        let none = PartialState {
+            url: URL.to_string(),
+            ..Default::default()
+        };
+        assert_eq!(none.if_range(), None);
+    }
+
+    #[test]
+    fn test_resumable_length() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let partial_path = temp_dir.path().join("node.tar.gz.partial");
+        let state_path = temp_dir.path().join("node.tar.gz.partial.json");
+
+        assert_eq!(resumable_length(&partial_path, &state_path, URL), None);
+
+        fs::write(&partial_path, b"12345").unwrap();
+        PartialState {
+            url: URL.to_string(),
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+        }
+        .save(&state_path)
+        .unwrap();
+
+        assert_eq!(
+            resumable_length(&partial_path, &state_path, URL),
+            Some((5, "\"abc\"".to_string()))
+        );
+        assert_eq!(resumable_length(&partial_path, &state_path, "https://example.com/other"), None);
+    }
 }
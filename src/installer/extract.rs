@@ -1,31 +1,100 @@
 use crate::errors::{NodeError, Result};
+use crate::installer::download::DownloadedArchive;
+use crate::installer::manifest::{write_manifest, InstallManifest};
 use flate2::read::GzDecoder;
 use indicatif::{ProgressBar, ProgressStyle};
-use std::fs::File;
+use std::fs::{self, File};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tar::Archive;
 
-pub fn extract_archive(archive_path: &Path, version: &str) -> Result<()> {
-    let versions_dir = crate::version::local::get_versions_dir();
-    println!("Extracting to: {}", versions_dir.display());
+pub const STAGING_PREFIX: &str = ".staging-";
+
+/// Staging dirs on platforms without a liveness check are removed after this long.
+const STALE_STAGING_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Unpacks into a private staging dir inside `versions/` and moves the result into place with
+/// a single rename, so an interrupted install never leaves a half-populated version behind.
+pub fn extract_archive(archive: &DownloadedArchive, version: &str) -> Result<()> {
+    let archive_path = archive.path.as_path();
+    let staging = create_staging_dir()?;
+    println!("Extracting to: {}", staging.path().display());
 
     if archive_path.extension().and_then(|e| e.to_str()) == Some("zip") {
-        extract_zip(archive_path, &versions_dir)?;
+        extract_zip(archive_path, staging.path())?;
     } else {
-        extract_tar_gz(archive_path, &versions_dir)?;
+        extract_tar_gz(archive_path, staging.path())?;
     }
 
-    let extracted_dir = find_extracted_dir(&versions_dir)?;
-    let target_dir = versions_dir.join(version);
+    let extracted_dir = find_extracted_dir(staging.path(), archive_path)?;
+    finish_install(&extracted_dir, version, &archive.url, Some(&archive.sha256))
+}
+
+/// A unique dir inside `versions/`, on the same filesystem as the final location so the
+/// rename in [`finish_install`] is atomic. It is removed when dropped.
+pub fn create_staging_dir() -> Result<tempfile::TempDir> {
+    let versions_dir = crate::version::local::get_versions_dir();
+    fs::create_dir_all(&versions_dir)?;
+    Ok(tempfile::Builder::new()
+        .prefix(&format!("{}{}-", STAGING_PREFIX, std::process::id()))
+        .tempdir_in(&versions_dir)?)
+}
+
+/// Validates a staged version, writes its manifest and renames it to `versions/<version>`.
+pub fn finish_install(staged_dir: &Path, version: &str, source_url: &str, sha256: Option<&str>) -> Result<()> {
+    validate_extracted_dir(staged_dir)?;
 
+    // The manifest goes in last, so a version dir that has one is always complete.
+    let manifest = InstallManifest::new(source_url, sha256, staged_dir)?;
+    write_manifest(staged_dir, &manifest)?;
+
+    let target_dir = crate::version::local::get_versions_dir().join(version);
     if target_dir.exists() {
-        std::fs::remove_dir_all(&target_dir)?;
+        fs::remove_dir_all(&target_dir)?;
     }
 
-    std::fs::rename(extracted_dir, target_dir)?;
+    fs::rename(staged_dir, target_dir)?;
     Ok(())
 }
 
+/// Removes staging dirs left behind by installs that crashed or were killed.
+pub fn clean_stale_staging_dirs() -> Result<usize> {
+    let versions_dir = crate::version::local::get_versions_dir();
+    if !versions_dir.exists() {
+        return Ok(0);
+    }
+
+    let mut removed = 0;
+    for entry in fs::read_dir(&versions_dir)? {
+        let entry = entry?;
+        let Some(pid) = entry.file_name().to_str().and_then(parse_staging_pid) else {
+            continue;
+        };
+
+        let stale = if cfg!(unix) {
+            !crate::utils::is_process_alive(pid)
+        } else {
+            entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age > STALE_STAGING_AGE)
+        };
+
+        if stale && fs::remove_dir_all(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+
+    Ok(removed)
+}
+
+fn parse_staging_pid(name: &str) -> Option<u32> {
+    let (pid, _) = name.strip_prefix(STAGING_PREFIX)?.split_once('-')?;
+    pid.parse().ok()
+}
+
 fn extract_zip(archive_path: &Path, target_dir: &Path) -> Result<()> {
     let file = File::open(archive_path)?;
     let mut archive = zip::ZipArchive::new(file)?;
@@ -78,19 +147,36 @@ fn extract_tar_gz(archive_path: &Path, target_dir: &Path) -> Result<()> {
     Ok(())
 }
 
-fn find_extracted_dir(parent_dir: &Path) -> Result<PathBuf> {
-    for entry in std::fs::read_dir(parent_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_dir() && path.file_name()
-            .and_then(|n| n.to_str())
-            .map(|s| s.starts_with("node-v"))
-            .unwrap_or(false)
-        {
-            return Ok(path);
-        }
+/// The archive's top-level dir, e.g. `node-v20.11.1-linux-x64` for `node-v20.11.1-linux-x64.tar.gz`.
+fn find_extracted_dir(staging_dir: &Path, archive_path: &Path) -> Result<PathBuf> {
+    let file_name = archive_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    let dir_name = file_name
+        .strip_suffix(".tar.gz")
+        .or_else(|| file_name.strip_suffix(".zip"))
+        .unwrap_or(file_name);
+
+    let extracted_dir = staging_dir.join(dir_name);
+    if !extracted_dir.is_dir() {
+        return Err(NodeError::ExtractionError(format!(
+            "{} does not contain {}",
+            file_name, dir_name
+        )));
+    }
+    Ok(extracted_dir)
+}
+
+fn validate_extracted_dir(extracted_dir: &Path) -> Result<()> {
+    let node_path = crate::version::local::get_node_binary(extracted_dir);
+    if !node_path.is_file() {
+        return Err(NodeError::ExtractionError(format!(
+            "Extracted archive is missing {}",
+            node_path.display()
+        )));
     }
-    Err(NodeError::ExtractionError("Could not find extracted directory".into()))
+    Ok(())
 }
 
 fn create_progress_bar(len: u64) -> ProgressBar {
@@ -105,7 +191,6 @@ fn create_progress_bar(len: u64) -> ProgressBar {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
 
     #[test]
     fn test_extract_tar_gz() {
@@ -113,4 +198,32 @@ mod tests {
         let archive_path = temp_dir.path().join("test.tar.gz");
         assert!(extract_tar_gz(&archive_path, temp_dir.path()).is_err());
     }
+
+    #[test]
+    fn test_find_extracted_dir() {
+        let staging = tempfile::tempdir().unwrap();
+        let archive = Path::new("/downloads/node-v20.11.1-linux-x64.tar.gz");
+        assert!(find_extracted_dir(staging.path(), archive).is_err());
+
+        // Leftovers from other versions must never be picked up.
+        fs::create_dir(staging.path().join("node-v18.19.1-linux-x64")).unwrap();
+        assert!(find_extracted_dir(staging.path(), archive).is_err());
+
+        let expected = staging.path().join("node-v20.11.1-linux-x64");
+        fs::create_dir(&expected).unwrap();
+        assert_eq!(find_extracted_dir(staging.path(), archive).unwrap(), expected);
+        assert!(validate_extracted_dir(&expected).is_err());
+
+        let node = crate::version::local::get_node_binary(&expected);
+        fs::create_dir_all(node.parent().unwrap()).unwrap();
+        fs::write(node, "").unwrap();
+        assert!(validate_extracted_dir(&expected).is_ok());
+    }
+
+    #[test]
+    fn test_parse_staging_pid() {
+        assert_eq!(parse_staging_pid(".staging-4242-aBc123"), Some(4242));
+        assert_eq!(parse_staging_pid("20.11.1"), None);
+        assert_eq!(parse_staging_pid(".staging-abc-aBc123"), None);
+    }
 }
\ No newline at end of file
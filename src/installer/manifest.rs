@@ -0,0 +1,90 @@
+use crate::errors::{NodeError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const MANIFEST_FILE: &str = ".rnvm-install.json";
+
+/// `source_url` of installs made by an rnvm that did not record where they came from.
+pub const LEGACY_SOURCE: &str = "legacy";
+
+/// Written as the last step of an install. A version dir without one is treated as broken.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstallManifest {
+    pub source_url: String,
+    /// SHA-256 of the downloaded archive; absent for versions imported from another manager.
+    pub sha256: Option<String>,
+    /// Seconds since the Unix epoch.
+    pub installed_at: u64,
+    pub rnvm_version: String,
+    pub file_count: u64,
+}
+
+impl InstallManifest {
+    /// Describes the files currently in `version_dir`.
+    pub fn new(source_url: &str, sha256: Option<&str>, version_dir: &Path) -> Result<Self> {
+        Ok(Self {
+            source_url: source_url.to_string(),
+            sha256: sha256.map(str::to_string),
+            installed_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            rnvm_version: env!("CARGO_PKG_VERSION").to_string(),
+            file_count: count_files(version_dir)?,
+        })
+    }
+}
+
+pub fn write_manifest(version_dir: &Path, manifest: &InstallManifest) -> Result<()> {
+    let content = serde_json::to_string_pretty(manifest)
+        .map_err(|e| NodeError::ConfigError(format!("Failed to serialize install manifest: {}", e)))?;
+
+    // Write then rename, so a crash can't leave a truncated manifest behind.
+    let partial = version_dir.join(format!("{}.partial", MANIFEST_FILE));
+    fs::write(&partial, content)?;
+    fs::rename(partial, version_dir.join(MANIFEST_FILE))?;
+    Ok(())
+}
+
+pub fn read_manifest(version_dir: &Path) -> Option<InstallManifest> {
+    let content = fs::read_to_string(version_dir.join(MANIFEST_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn count_files(dir: &Path) -> Result<u64> {
+    let mut count = 0;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            count += count_files(&entry.path())?;
+        } else if entry.file_name() != MANIFEST_FILE {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join("bin")).unwrap();
+        fs::write(temp_dir.path().join("bin").join("node"), "").unwrap();
+        fs::write(temp_dir.path().join("LICENSE"), "").unwrap();
+        assert!(read_manifest(temp_dir.path()).is_none());
+
+        let manifest = InstallManifest::new("https://nodejs.org/dist/x.tar.gz", Some("abc"), temp_dir.path()).unwrap();
+        assert_eq!(manifest.file_count, 2);
+        write_manifest(temp_dir.path(), &manifest).unwrap();
+        assert_eq!(read_manifest(temp_dir.path()), Some(manifest));
+
+        fs::write(temp_dir.path().join(MANIFEST_FILE), "{\"source_url\":").unwrap();
+        assert!(read_manifest(temp_dir.path()).is_none());
+    }
+}
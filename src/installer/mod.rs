@@ -1,22 +1,60 @@
 pub mod download;
 pub mod extract;
+pub mod manifest;
+pub mod signature;
+pub mod verify;
 
-use crate::errors::Result;
+use crate::errors::{NodeError, Result};
 use crate::version::NodeVersion;
 
+/// Returns the installed version matching `version_spec`, installing the best remote match if none is.
+pub async fn ensure_installed(version_spec: &str) -> Result<String> {
+    if let Some(installed) = crate::version::find_installed_version(version_spec)? {
+        return Ok(installed.version_str());
+    }
+
+    let resolved = crate::version::get_matching_version(version_spec).await?;
+    eprintln!("Version {} is not installed. Installing...", resolved.version_str());
+    install_version(&resolved).await?;
+    Ok(resolved.version_str())
+}
+
 pub async fn install_version(version: &NodeVersion) -> Result<()> {
     if crate::version::local::is_installed(&version.version_str())? {
         return Ok(());
     }
 
+    // Another process may have finished the same install while we waited.
+    let _lock = crate::lock::lock_version(&version.version_str())?;
+    if crate::version::local::is_installed(&version.version_str())? {
+        return Ok(());
+    }
+
+    crate::version::remote::ensure_online(&format!("Installing Node.js {}", version.version_str()))?;
+
+    let platform = crate::version::remote::get_platform_file_key();
+    if !version.files.is_empty() && !version.files.contains(&platform) {
+        return Err(NodeError::VersionNotFound(format!(
+            "Node.js {} has no prebuilt binary for {}",
+            version.version_str(),
+            platform
+        )));
+    }
+
     let download_dir = crate::utils::get_base_dir().join("downloads");
     std::fs::create_dir_all(&download_dir)?;
 
-    let archive_path = download::download_version(version, &download_dir).await?;
+    let checksums = verify::fetch_checksums(version).await?;
+    let archive = download::download_version(version, &download_dir).await?;
+
+    if let Err(e) = verify::verify_archive(&archive, &checksums) {
+        std::fs::remove_file(&archive.path)?;
+        return Err(e);
+    }
 
-    extract::extract_archive(&archive_path, &version.version_str())?;
+    extract::extract_archive(&archive, &version.version_str())?;
 
-    std::fs::remove_file(archive_path)?;
+    std::fs::remove_file(&archive.path)?;
 
     Ok(())
 }
\ No newline at end of file
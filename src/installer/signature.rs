@@ -0,0 +1,209 @@
+use crate::errors::{NodeError, Result};
+use reqwest::Client;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+const RELEASE_KEYS_URL: &str = "https://raw.githubusercontent.com/nodejs/release-keys/HEAD";
+
+/// The Node.js release keys from nodejs/release-keys at build time. Refreshed with
+/// `keys/update.sh`; `rnvm keys update` saves newer keys that take precedence.
+const BUNDLED_KEYRING: &[u8] = include_bytes!("../../keys/nodejs-release-keys.asc");
+
+/// Returns the keys to verify against: the configured keyring, then keys saved by
+/// `rnvm keys update`, then the keys bundled with rnvm.
+pub fn load_keyring() -> Result<Vec<u8>> {
+    if let Some(path) = crate::config::settings::configured_release_keyring()? {
+        return fs::read(&path).map_err(|e| {
+            NodeError::SignatureError(format!("Could not read release keyring {}: {}", path.display(), e))
+        });
+    }
+
+    let updated = crate::config::settings::release_keyring()?;
+    if updated.exists() {
+        return Ok(fs::read(updated)?);
+    }
+
+    if !contains_keys(BUNDLED_KEYRING) {
+        return Err(NodeError::SignatureError(
+            "This build of rnvm has no bundled release keys. Run 'rnvm keys update' first.".to_string(),
+        ));
+    }
+    Ok(BUNDLED_KEYRING.to_vec())
+}
+
+fn contains_keys(keyring: &[u8]) -> bool {
+    String::from_utf8_lossy(keyring).contains("-----BEGIN PGP PUBLIC KEY BLOCK-----")
+}
+
+/// Downloads the current Node.js release team keys into the configured keyring file.
+pub async fn update_keyring(keyring: &Path) -> Result<usize> {
+    crate::version::remote::ensure_online("Updating the release keys")?;
+
+    let client = Client::new();
+    let key_list = fetch_text(&client, &format!("{}/keys.list", RELEASE_KEYS_URL)).await?;
+
+    let mut armored = String::new();
+    let mut count = 0;
+    for fingerprint in key_list.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let key = fetch_text(&client, &format!("{}/keys/{}.asc", RELEASE_KEYS_URL, fingerprint)).await?;
+        armored.push_str(key.trim_end());
+        armored.push('\n');
+        count += 1;
+    }
+
+    if count == 0 || !contains_keys(armored.as_bytes()) {
+        return Err(NodeError::SignatureError("Release key list is empty".to_string()));
+    }
+
+    if let Some(parent) = keyring.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(keyring, armored)?;
+    Ok(count)
+}
+
+async fn fetch_text(client: &Client, url: &str) -> Result<String> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| NodeError::DownloadError(e.to_string()))?;
+
+    if !response.status().is_success() {
+        return Err(NodeError::DownloadError(format!(
+            "Failed to fetch {}: HTTP {}",
+            url,
+            response.status()
+        )));
+    }
+
+    response
+        .text()
+        .await
+        .map_err(|e| NodeError::DownloadError(e.to_string()))
+}
+
+/// Checks a detached signature over `data` using only the armored keys in `keyring`.
+///
+/// GnuPG runs against a throwaway home directory so the user's own keyring and
+/// trust settings never influence the result.
+pub fn verify_detached(keyring: &[u8], data: &[u8], signature: &[u8]) -> Result<()> {
+    let home = tempfile::tempdir()?;
+    let keyring_path = home.path().join("release-keys.asc");
+    let data_path = home.path().join("SHASUMS256.txt");
+    let sig_path = home.path().join("SHASUMS256.txt.sig");
+    fs::write(&keyring_path, keyring)?;
+    fs::write(&data_path, data)?;
+    fs::write(&sig_path, signature)?;
+
+    let import = gpg(home.path(), &["--import".as_ref(), keyring_path.as_os_str()])?;
+    if !import.status.success() {
+        return Err(NodeError::SignatureError(format!(
+            "Failed to import release keys: {}",
+            String::from_utf8_lossy(&import.stderr).trim()
+        )));
+    }
+
+    let verify = gpg(
+        home.path(),
+        &[
+            "--status-fd".as_ref(),
+            "1".as_ref(),
+            "--verify".as_ref(),
+            sig_path.as_os_str(),
+            data_path.as_os_str(),
+        ],
+    )?;
+    let status = String::from_utf8_lossy(&verify.stdout);
+    if !verify.status.success() || !status.lines().any(|l| l.starts_with("[GNUPG:] VALIDSIG")) {
+        return Err(NodeError::SignatureError(
+            "SHASUMS256.txt is not signed by a known Node.js release key".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn gpg(home: &Path, args: &[&std::ffi::OsStr]) -> Result<Output> {
+    Command::new("gpg")
+        .arg("--batch")
+        .arg("--no-tty")
+        .arg("--homedir")
+        .arg(home)
+        .args(args)
+        .output()
+        .map_err(|e| {
+            NodeError::SignatureError(format!("Could not run gpg ({}). Install GnuPG to verify signatures.", e))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHASUMS: &[u8] = b"0000  node-v20.11.1-linux-x64.tar.gz\n";
+
+    fn gpg_available() -> bool {
+        Command::new("gpg").arg("--version").output().is_ok()
+    }
+
+    /// Generates a throwaway signing key and returns its home dir and exported public keyring.
+    fn generate_key() -> (tempfile::TempDir, Vec<u8>) {
+        let home = tempfile::tempdir().unwrap();
+        let status = Command::new("gpg")
+            .args(["--batch", "--homedir"])
+            .arg(home.path())
+            .args(["--passphrase", "", "--quick-gen-key", "rnvm test <test@example.com>", "ed25519", "sign", "never"])
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success());
+
+        let export = gpg(home.path(), &["--armor".as_ref(), "--export".as_ref()]).unwrap();
+        (home, export.stdout)
+    }
+
+    fn sign(home: &Path, data_path: &Path) -> Vec<u8> {
+        let sig_path = data_path.with_extension("txt.sig");
+        let status = Command::new("gpg")
+            .args(["--batch", "--homedir"])
+            .arg(home)
+            .args(["--pinentry-mode", "loopback", "--passphrase", "", "--detach-sign", "--output"])
+            .arg(&sig_path)
+            .arg(data_path)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success());
+        fs::read(sig_path).unwrap()
+    }
+
+    #[test]
+    fn test_bundled_keyring() {
+        // Fails until keys/update.sh has been run and its output committed.
+        assert!(contains_keys(BUNDLED_KEYRING), "keys/nodejs-release-keys.asc has no release keys");
+    }
+
+    #[test]
+    fn test_verify_detached() {
+        if !gpg_available() {
+            return;
+        }
+
+        let dist = tempfile::tempdir().unwrap();
+        let data_path = dist.path().join("SHASUMS256.txt");
+        fs::write(&data_path, SHASUMS).unwrap();
+
+        let (signer_home, keyring) = generate_key();
+        let signature = sign(signer_home.path(), &data_path);
+
+        assert!(verify_detached(&keyring, SHASUMS, &signature).is_ok());
+        assert!(verify_detached(&keyring, b"1111  node-v20.11.1-linux-x64.tar.gz\n", &signature).is_err());
+
+        let (_other_home, other_keyring) = generate_key();
+        assert!(verify_detached(&other_keyring, SHASUMS, &signature).is_err());
+
+        assert!(verify_detached(b"", SHASUMS, &signature).is_err());
+    }
+}
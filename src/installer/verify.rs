@@ -0,0 +1,119 @@
+use crate::errors::{NodeError, Result};
+use crate::installer::download::DownloadedArchive;
+use crate::version::NodeVersion;
+use reqwest::Client;
+use std::collections::HashMap;
+
+pub async fn fetch_checksums(version: &NodeVersion) -> Result<HashMap<String, String>> {
+    let client = Client::new();
+    let content = fetch_bytes(&client, &crate::version::remote::get_shasums_url(version)?).await?;
+
+    if crate::config::settings::verify_signature()? {
+        let signature =
+            fetch_bytes(&client, &crate::version::remote::get_shasums_signature_url(version)?).await?;
+        let keyring = crate::installer::signature::load_keyring()?;
+        crate::installer::signature::verify_detached(&keyring, &content, &signature)?;
+    }
+
+    Ok(parse_checksums(&String::from_utf8_lossy(&content)))
+}
+
+async fn fetch_bytes(client: &Client, url: &str) -> Result<Vec<u8>> {
+    let dist_url = crate::version::remote::get_dist_url()?;
+    let response = crate::version::remote::authorize(client.get(url), &dist_url)
+        .send()
+        .await
+        .map_err(|e| NodeError::DownloadError(e.to_string()))?;
+
+    if !response.status().is_success() {
+        return Err(NodeError::ChecksumError(format!(
+            "Failed to fetch {}: HTTP {}",
+            url,
+            response.status()
+        )));
+    }
+
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| NodeError::DownloadError(e.to_string()))?;
+    Ok(bytes.to_vec())
+}
+
+/// Parses a `SHASUMS256.txt` file into a map of file name to lowercase hex digest.
+pub fn parse_checksums(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let hash = parts.next()?;
+            let filename = parts.next()?.trim_start_matches('*');
+            Some((filename.to_string(), hash.to_lowercase()))
+        })
+        .collect()
+}
+
+pub fn verify_archive(archive: &DownloadedArchive, checksums: &HashMap<String, String>) -> Result<()> {
+    let filename = archive.filename();
+    let expected = checksums.get(filename).ok_or_else(|| {
+        NodeError::ChecksumError(format!("No checksum listed for {}", filename))
+    })?;
+
+    if *expected != archive.sha256 {
+        return Err(NodeError::ChecksumError(format!(
+            "{} expected {}, got {}",
+            filename, expected, archive.sha256
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const SHASUMS: &str = "\
+1f2e3d4c5b6a79881f2e3d4c5b6a79881f2e3d4c5b6a79881f2e3d4c5b6a7988  node-v20.11.1-linux-x64.tar.gz
+ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789  node-v20.11.1-win-x64.zip
+";
+
+    fn archive(filename: &str, sha256: &str) -> DownloadedArchive {
+        DownloadedArchive {
+            path: PathBuf::from("/tmp").join(filename),
+            url: format!("https://nodejs.org/dist/v20.11.1/{}", filename),
+            sha256: sha256.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_checksums() {
+        let checksums = parse_checksums(SHASUMS);
+        assert_eq!(checksums.len(), 2);
+        assert_eq!(
+            checksums["node-v20.11.1-win-x64.zip"],
+            "abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789"
+        );
+    }
+
+    #[test]
+    fn test_verify_archive() {
+        let checksums = parse_checksums(SHASUMS);
+
+        let good = archive(
+            "node-v20.11.1-linux-x64.tar.gz",
+            "1f2e3d4c5b6a79881f2e3d4c5b6a79881f2e3d4c5b6a79881f2e3d4c5b6a7988",
+        );
+        assert!(verify_archive(&good, &checksums).is_ok());
+
+        let tampered = archive("node-v20.11.1-linux-x64.tar.gz", "00");
+        assert!(matches!(
+            verify_archive(&tampered, &checksums),
+            Err(NodeError::ChecksumError(_))
+        ));
+
+        let unlisted = archive("node-v20.11.1-darwin-arm64.tar.gz", "00");
+        assert!(verify_archive(&unlisted, &checksums).is_err());
+    }
+}
@@ -0,0 +1,103 @@
+use crate::errors::{NodeError, Result};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{Seek, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// An advisory lock on a file in `~/.rnvm/locks`. The OS releases it when this is dropped
+/// or the process dies, so a crashed rnvm never leaves a stale lock behind.
+pub struct FileLock {
+    _file: File,
+}
+
+pub fn get_locks_dir() -> PathBuf {
+    crate::utils::get_base_dir().join("locks")
+}
+
+/// Serializes installs and uninstalls of a single version.
+pub fn lock_version(version: &str) -> Result<FileLock> {
+    acquire(&format!("version-{}", version))
+}
+
+/// Serializes writes to shared configuration: aliases and the `current` links.
+pub fn lock_config() -> Result<FileLock> {
+    acquire("config")
+}
+
+fn acquire(name: &str) -> Result<FileLock> {
+    let locks_dir = get_locks_dir();
+    fs::create_dir_all(&locks_dir)?;
+    let timeout = crate::config::settings::lock_timeout()?;
+    acquire_at(&locks_dir.join(format!("{}.lock", name)), timeout)
+}
+
+fn acquire_at(path: &Path, timeout: Duration) -> Result<FileLock> {
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)?;
+
+    let started = Instant::now();
+    let mut waiting = false;
+    loop {
+        match file.try_lock() {
+            Ok(()) => break,
+            Err(TryLockError::WouldBlock) => {}
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+
+        if started.elapsed() >= timeout {
+            return Err(NodeError::LockError(format!(
+                "Timed out after {}s waiting for {} held by {}",
+                timeout.as_secs(),
+                path.display(),
+                holder(path)
+            )));
+        }
+        if !waiting {
+            eprintln!("Waiting for lock {} held by {}...", path.display(), holder(path));
+            waiting = true;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+
+    // Record ourselves as the holder for anyone who has to wait.
+    file.set_len(0)?;
+    file.rewind()?;
+    write!(file, "{}", std::process::id())?;
+    Ok(FileLock { _file: file })
+}
+
+fn holder(path: &Path) -> String {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| content.trim().parse::<u32>().ok())
+        .map(|pid| format!("pid {}", pid))
+        .unwrap_or_else(|| "another process".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_is_exclusive() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("config.lock");
+
+        let held = acquire_at(&path, Duration::ZERO).unwrap();
+        match acquire_at(&path, Duration::ZERO) {
+            Err(NodeError::LockError(message)) => {
+                assert!(message.contains(&format!("pid {}", std::process::id())), "{}", message)
+            }
+            _ => panic!("lock should be held"),
+        }
+
+        drop(held);
+        assert!(acquire_at(&path, Duration::ZERO).is_ok());
+    }
+}
@@ -1,9 +1,15 @@
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 
 mod cli;
 mod config;
+mod doctor;
 mod errors;
+mod exec;
+mod import;
 mod installer;
+mod lock;
+mod shell;
+mod shims;
 mod utils;
 mod version;
 
@@ -12,6 +18,18 @@ mod version;
 async fn main() -> errors::Result<()> {
     env_logger::init();
 
+    if let Some(name) = shims::invoked_shim() {
+        if let Err(e) = shims::dispatch(&name) {
+            eprintln!("rnvm: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    clap_complete::CompleteEnv::with_factory(cli::Cli::command)
+        .var(shell::completions::COMPLETE_VAR)
+        .complete();
+
     let cli = cli::Cli::parse();
 
     if let Err(e) = cli::run_with(cli).await {
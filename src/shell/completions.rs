@@ -0,0 +1,104 @@
+use crate::errors::{NodeError, Result};
+use crate::version::NodeVersion;
+use clap_complete::engine::CompletionCandidate;
+use clap_complete::env::Shells;
+use std::io::Write;
+
+/// Environment variable the registration scripts set when asking rnvm for completions.
+pub const COMPLETE_VAR: &str = "RNVM_COMPLETE";
+
+pub const SHELLS: [&str; 5] = ["bash", "zsh", "fish", "powershell", "elvish"];
+
+/// Writes the script that hooks `shell`'s completion up to `RNVM_COMPLETE=<shell> rnvm -- ...`.
+pub fn write_registration(shell: &str, buf: &mut dyn Write) -> Result<()> {
+    let shells = Shells::builtins();
+    let completer = shells
+        .completer(shell)
+        .ok_or_else(|| NodeError::ConfigError(format!("Completions are not supported for {}", shell)))?;
+    completer.write_registration(COMPLETE_VAR, "rnvm", "rnvm", "rnvm", buf)?;
+    Ok(())
+}
+
+pub fn installed_versions() -> Vec<CompletionCandidate> {
+    crate::version::local::get_installed_versions()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|v| CompletionCandidate::new(v.version_str()))
+        .collect()
+}
+
+pub fn alias_names() -> Vec<CompletionCandidate> {
+    let mut aliases: Vec<_> = crate::config::alias::list_aliases()
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    aliases.sort();
+    aliases
+        .into_iter()
+        .map(|(name, version)| CompletionCandidate::new(name).help(Some(version.into())))
+        .collect()
+}
+
+/// `lts` and `lts/<codename>` for every line in the cached index; never hits the network.
+pub fn lts_specs() -> Vec<CompletionCandidate> {
+    let versions = crate::version::cache::read_cached_index()
+        .and_then(|content| crate::version::remote::parse_version_list(&content).ok())
+        .unwrap_or_default();
+    lts_spec_names(&versions)
+        .into_iter()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Everything `use` accepts: installed versions, aliases and LTS specs.
+pub fn version_specs() -> Vec<CompletionCandidate> {
+    let mut candidates = installed_versions();
+    candidates.extend(alias_names());
+    candidates.extend(lts_specs());
+    candidates
+}
+
+fn lts_spec_names(versions: &[NodeVersion]) -> Vec<String> {
+    let mut names = vec!["lts".to_string()];
+    for codename in versions.iter().filter_map(|v| v.lts_codename.as_deref()) {
+        let name = format!("lts/{}", codename.to_lowercase());
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use semver::Version;
+
+    fn lts(version: &str, codename: Option<&str>) -> NodeVersion {
+        let mut v = NodeVersion::new(Version::parse(version).unwrap(), codename.is_some(), String::new());
+        v.lts_codename = codename.map(str::to_string);
+        v
+    }
+
+    #[test]
+    fn test_lts_spec_names() {
+        let versions = vec![
+            lts("21.6.2", None),
+            lts("20.11.1", Some("Iron")),
+            lts("20.10.0", Some("Iron")),
+            lts("18.19.1", Some("Hydrogen")),
+        ];
+        assert_eq!(lts_spec_names(&versions), vec!["lts", "lts/iron", "lts/hydrogen"]);
+        assert_eq!(lts_spec_names(&[]), vec!["lts"]);
+    }
+
+    #[test]
+    fn test_write_registration() {
+        for shell in SHELLS {
+            let mut buf = Vec::new();
+            write_registration(shell, &mut buf).unwrap();
+            assert!(String::from_utf8(buf).unwrap().contains(COMPLETE_VAR), "{}", shell);
+        }
+        assert!(write_registration("nushell", &mut Vec::new()).is_err());
+    }
+}
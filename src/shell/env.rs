@@ -0,0 +1,254 @@
+use crate::shell::Shell;
+use std::path::{Path, PathBuf};
+
+/// Paths exported by `rnvm env`.
+pub struct ShellEnv {
+    pub base_dir: PathBuf,
+    pub multishell_path: PathBuf,
+    pub bin_dir: PathBuf,
+}
+
+impl ShellEnv {
+    pub fn new(base_dir: &Path, multishell_path: &Path) -> Self {
+        Self {
+            base_dir: base_dir.to_path_buf(),
+            multishell_path: multishell_path.to_path_buf(),
+            bin_dir: crate::version::local::get_bin_dir(multishell_path),
+        }
+    }
+}
+
+/// Renders the script that `eval "$(rnvm env)"` (or the shell's equivalent) runs.
+pub fn render(shell: Shell, env: &ShellEnv) -> String {
+    let base_dir = shell.quote(&env.base_dir.to_string_lossy());
+    let multishell_path = shell.quote(&env.multishell_path.to_string_lossy());
+    let bin_dir = shell.quote(&env.bin_dir.to_string_lossy());
+
+    match shell {
+        Shell::Bash | Shell::Zsh => format!(
+            "export RNVM_DIR={}\n\
+             export RNVM_MULTISHELL_PATH={}\n\
+             export PATH={}\"${{PATH:+:$PATH}}\"\n",
+            base_dir, multishell_path, bin_dir
+        ),
+        Shell::Fish => format!(
+            "set -gx RNVM_DIR {}\n\
+             set -gx RNVM_MULTISHELL_PATH {}\n\
+             set -gx PATH {} $PATH\n",
+            base_dir, multishell_path, bin_dir
+        ),
+        Shell::Powershell => format!(
+            "$env:RNVM_DIR = {}\n\
+             $env:RNVM_MULTISHELL_PATH = {}\n\
+             $env:PATH = {} + [System.IO.Path]::PathSeparator + $env:PATH\n",
+            base_dir, multishell_path, bin_dir
+        ),
+        Shell::Nushell => format!(
+            "$env.RNVM_DIR = {}\n\
+             $env.RNVM_MULTISHELL_PATH = {}\n\
+             $env.PATH = ($env.PATH | split row (char esep) | prepend {})\n",
+            base_dir, multishell_path, bin_dir
+        ),
+    }
+}
+
+/// Renders the same variables as [`render`] as `{"env": {...}, "path": "<bin dir>"}`. nushell reads
+/// it with `from json` on every start, so each session gets its own link instead of one saved file.
+pub fn render_json(env: &ShellEnv) -> String {
+    serde_json::json!({
+        "env": {
+            "RNVM_DIR": env.base_dir,
+            "RNVM_MULTISHELL_PATH": env.multishell_path,
+        },
+        "path": env.bin_dir,
+    })
+    .to_string()
+}
+
+/// Renders a hook that runs `rnvm use --silent --if-changed` whenever the working directory changes.
+pub fn render_use_on_cd(shell: Shell, install_if_missing: bool) -> String {
+    let command = if install_if_missing {
+        "rnvm use --silent --if-changed --install-if-missing"
+    } else {
+        "rnvm use --silent --if-changed"
+    };
+
+    match shell {
+        Shell::Bash => format!(
+            "__rnvm_use_on_cd() {{\n  \
+               if [ \"$__RNVM_LAST_PWD\" != \"$PWD\" ]; then\n    \
+                 __RNVM_LAST_PWD=\"$PWD\"\n    \
+                 {}\n  \
+               fi\n\
+             }}\n\
+             case \";${{PROMPT_COMMAND:-}};\" in\n  \
+               *\";__rnvm_use_on_cd;\"*) ;;\n  \
+               *) PROMPT_COMMAND=\"__rnvm_use_on_cd${{PROMPT_COMMAND:+;$PROMPT_COMMAND}}\" ;;\n\
+             esac\n",
+            command
+        ),
+        Shell::Zsh => format!(
+            "autoload -U add-zsh-hook\n\
+             __rnvm_use_on_cd() {{\n  \
+               {}\n\
+             }}\n\
+             add-zsh-hook chpwd __rnvm_use_on_cd\n\
+             __rnvm_use_on_cd\n",
+            command
+        ),
+        Shell::Fish => format!(
+            "function __rnvm_use_on_cd --on-variable PWD\n    \
+               {}\n\
+             end\n\
+             __rnvm_use_on_cd\n",
+            command
+        ),
+        // Chain onto an existing handler; the guard keeps a re-sourced profile from chaining onto us.
+        Shell::Powershell => format!(
+            "if (-not $global:__rnvm_use_on_cd) {{\n    \
+               $global:__rnvm_use_on_cd = $true\n    \
+               $__rnvm_previous_action = $ExecutionContext.SessionState.InvokeCommand.LocationChangedAction\n    \
+               $ExecutionContext.SessionState.InvokeCommand.LocationChangedAction = {{\n        \
+                 if ($__rnvm_previous_action) {{ $__rnvm_previous_action.Invoke($args[0], $args[1]) }}\n        \
+                 {}\n    \
+               }}.GetNewClosure()\n\
+             }}\n\
+             {}\n",
+            command, command
+        ),
+        Shell::Nushell => format!(
+            "$env.config = ($env.config | upsert hooks.env_change.PWD {{|config|\n    \
+               ($config.hooks?.env_change?.PWD? | default []) | append {{|before, after| ^{} }}\n\
+             }})\n",
+            command
+        ),
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn env() -> ShellEnv {
+        ShellEnv::new(
+            Path::new("/home/user/.rnvm"),
+            Path::new("/home/user/.rnvm/multishells/4242_1700000000000"),
+        )
+    }
+
+    #[test]
+    fn test_render_bash() {
+        assert_eq!(
+            render(Shell::Bash, &env()),
+            "export RNVM_DIR=\"/home/user/.rnvm\"\n\
+             export RNVM_MULTISHELL_PATH=\"/home/user/.rnvm/multishells/4242_1700000000000\"\n\
+             export PATH=\"/home/user/.rnvm/multishells/4242_1700000000000/bin\"\"${PATH:+:$PATH}\"\n"
+        );
+        assert_eq!(render(Shell::Zsh, &env()), render(Shell::Bash, &env()));
+    }
+
+    #[test]
+    fn test_render_fish() {
+        assert_eq!(
+            render(Shell::Fish, &env()),
+            "set -gx RNVM_DIR '/home/user/.rnvm'\n\
+             set -gx RNVM_MULTISHELL_PATH '/home/user/.rnvm/multishells/4242_1700000000000'\n\
+             set -gx PATH '/home/user/.rnvm/multishells/4242_1700000000000/bin' $PATH\n"
+        );
+    }
+
+    #[test]
+    fn test_render_powershell() {
+        assert_eq!(
+            render(Shell::Powershell, &env()),
+            "$env:RNVM_DIR = '/home/user/.rnvm'\n\
+             $env:RNVM_MULTISHELL_PATH = '/home/user/.rnvm/multishells/4242_1700000000000'\n\
+             $env:PATH = '/home/user/.rnvm/multishells/4242_1700000000000/bin' + [System.IO.Path]::PathSeparator + $env:PATH\n"
+        );
+    }
+
+    #[test]
+    fn test_render_use_on_cd_bash() {
+        assert_eq!(
+            render_use_on_cd(Shell::Bash, false),
+            "__rnvm_use_on_cd() {\n  \
+               if [ \"$__RNVM_LAST_PWD\" != \"$PWD\" ]; then\n    \
+                 __RNVM_LAST_PWD=\"$PWD\"\n    \
+                 rnvm use --silent --if-changed\n  \
+               fi\n\
+             }\n\
+             case \";${PROMPT_COMMAND:-};\" in\n  \
+               *\";__rnvm_use_on_cd;\"*) ;;\n  \
+               *) PROMPT_COMMAND=\"__rnvm_use_on_cd${PROMPT_COMMAND:+;$PROMPT_COMMAND}\" ;;\n\
+             esac\n"
+        );
+    }
+
+    #[test]
+    fn test_render_use_on_cd_zsh() {
+        assert_eq!(
+            render_use_on_cd(Shell::Zsh, true),
+            "autoload -U add-zsh-hook\n\
+             __rnvm_use_on_cd() {\n  \
+               rnvm use --silent --if-changed --install-if-missing\n\
+             }\n\
+             add-zsh-hook chpwd __rnvm_use_on_cd\n\
+             __rnvm_use_on_cd\n"
+        );
+    }
+
+    #[test]
+    fn test_render_use_on_cd_fish() {
+        assert_eq!(
+            render_use_on_cd(Shell::Fish, false),
+            "function __rnvm_use_on_cd --on-variable PWD\n    \
+               rnvm use --silent --if-changed\n\
+             end\n\
+             __rnvm_use_on_cd\n"
+        );
+    }
+
+    #[test]
+    fn test_render_use_on_cd_powershell() {
+        assert_eq!(
+            render_use_on_cd(Shell::Powershell, false),
+            "if (-not $global:__rnvm_use_on_cd) {\n    \
+               $global:__rnvm_use_on_cd = $true\n    \
+               $__rnvm_previous_action = $ExecutionContext.SessionState.InvokeCommand.LocationChangedAction\n    \
+               $ExecutionContext.SessionState.InvokeCommand.LocationChangedAction = {\n        \
+                 if ($__rnvm_previous_action) { $__rnvm_previous_action.Invoke($args[0], $args[1]) }\n        \
+                 rnvm use --silent --if-changed\n    \
+               }.GetNewClosure()\n\
+             }\n\
+             rnvm use --silent --if-changed\n"
+        );
+    }
+
+    #[test]
+    fn test_render_use_on_cd_nushell() {
+        assert_eq!(
+            render_use_on_cd(Shell::Nushell, false),
+            "$env.config = ($env.config | upsert hooks.env_change.PWD {|config|\n    \
+               ($config.hooks?.env_change?.PWD? | default []) | append {|before, after| ^rnvm use --silent --if-changed }\n\
+             })\n"
+        );
+    }
+
+    #[test]
+    fn test_render_json() {
+        let json: serde_json::Value = serde_json::from_str(&render_json(&env())).unwrap();
+        assert_eq!(json["env"]["RNVM_DIR"], "/home/user/.rnvm");
+        assert_eq!(json["env"]["RNVM_MULTISHELL_PATH"], "/home/user/.rnvm/multishells/4242_1700000000000");
+        assert_eq!(json["path"], "/home/user/.rnvm/multishells/4242_1700000000000/bin");
+    }
+
+    #[test]
+    fn test_render_nushell() {
+        assert_eq!(
+            render(Shell::Nushell, &env()),
+            "$env.RNVM_DIR = \"/home/user/.rnvm\"\n\
+             $env.RNVM_MULTISHELL_PATH = \"/home/user/.rnvm/multishells/4242_1700000000000\"\n\
+             $env.PATH = ($env.PATH | split row (char esep) | prepend \"/home/user/.rnvm/multishells/4242_1700000000000/bin\")\n"
+        );
+    }
+}
@@ -0,0 +1,111 @@
+pub mod completions;
+pub mod env;
+pub mod multishell;
+
+use crate::errors::{NodeError, Result};
+use clap::ValueEnum;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    #[value(alias = "pwsh")]
+    Powershell,
+    Nushell,
+}
+
+impl Shell {
+    /// Detects the calling shell from the parent process, then `$SHELL`.
+    pub fn detect() -> Result<Self> {
+        if let Some(shell) = parent_process_name().as_deref().and_then(Self::from_program) {
+            return Ok(shell);
+        }
+
+        if cfg!(windows) {
+            return Ok(Shell::Powershell);
+        }
+
+        std::env::var("SHELL")
+            .ok()
+            .as_deref()
+            .and_then(Self::from_program)
+            .ok_or_else(|| {
+                NodeError::ConfigError("Could not detect your shell. Pass it explicitly with --shell.".to_string())
+            })
+    }
+
+    fn from_program(program: &str) -> Option<Self> {
+        let name = Path::new(program.trim())
+            .file_stem()?
+            .to_str()?
+            .trim_start_matches('-')
+            .to_lowercase();
+
+        match name.as_str() {
+            "bash" | "sh" | "dash" => Some(Shell::Bash),
+            "zsh" => Some(Shell::Zsh),
+            "fish" => Some(Shell::Fish),
+            "pwsh" | "powershell" => Some(Shell::Powershell),
+            "nu" | "nushell" => Some(Shell::Nushell),
+            _ => None,
+        }
+    }
+
+    /// Quotes `value` as a string literal for this shell.
+    pub fn quote(&self, value: &str) -> String {
+        match self {
+            Shell::Bash | Shell::Zsh => {
+                let mut quoted = String::with_capacity(value.len() + 2);
+                quoted.push('"');
+                for c in value.chars() {
+                    if matches!(c, '"' | '\\' | '$' | '`') {
+                        quoted.push('\\');
+                    }
+                    quoted.push(c);
+                }
+                quoted.push('"');
+                quoted
+            }
+            Shell::Fish => format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'")),
+            Shell::Powershell => format!("'{}'", value.replace('\'', "''")),
+            Shell::Nushell => format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"")),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn parent_process_name() -> Option<String> {
+    let ppid = std::os::unix::process::parent_id();
+    std::fs::read_to_string(format!("/proc/{}/comm", ppid))
+        .ok()
+        .map(|name| name.trim().to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn parent_process_name() -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_from_program() {
+        assert_eq!(Shell::from_program("/bin/bash"), Some(Shell::Bash));
+        assert_eq!(Shell::from_program("-zsh"), Some(Shell::Zsh));
+        assert_eq!(Shell::from_program("/usr/local/bin/fish"), Some(Shell::Fish));
+        assert_eq!(Shell::from_program("pwsh.exe"), Some(Shell::Powershell));
+        assert_eq!(Shell::from_program("nu"), Some(Shell::Nushell));
+        assert_eq!(Shell::from_program("/bin/tcsh"), None);
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(Shell::Bash.quote("/home/a b/$x"), "\"/home/a b/\\$x\"");
+        assert_eq!(Shell::Fish.quote("/home/it's"), "'/home/it\\'s'");
+        assert_eq!(Shell::Powershell.quote("C:\\it's"), "'C:\\it''s'");
+    }
+}
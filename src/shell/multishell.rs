@@ -0,0 +1,112 @@
+use crate::errors::Result;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Sessions on platforms without a liveness check are collected after this long.
+const STALE_SESSION_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+pub fn get_multishell_dir() -> PathBuf {
+    crate::utils::get_base_dir().join("multishells")
+}
+
+/// Creates a session link named `<shell pid>_<timestamp>` that starts out on the active version.
+///
+/// `rnvm env` is run by the shell itself (`eval "$(rnvm env)"`), so our parent is the session owner.
+pub fn create_session() -> Result<PathBuf> {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let link = get_multishell_dir().join(format!("{}_{}", parent_pid(), millis));
+
+    fs::create_dir_all(get_multishell_dir())?;
+    if let Some(version) = crate::version::local::get_current_version()? {
+        let version_dir = crate::version::local::get_versions_dir().join(version);
+        crate::version::local::replace_link(&version_dir, &link)?;
+    }
+
+    Ok(link)
+}
+
+/// Removes session links whose shell has exited. Returns how many were removed.
+pub fn collect_garbage() -> Result<usize> {
+    let mut removed = 0;
+    for (link, live) in sessions()? {
+        if !live && crate::version::local::remove_link(&link).is_ok() {
+            removed += 1;
+        }
+    }
+
+    Ok(removed)
+}
+
+/// Session links of shells that are still running.
+pub fn live_sessions() -> Result<Vec<PathBuf>> {
+    Ok(sessions()?
+        .into_iter()
+        .filter_map(|(link, live)| live.then_some(link))
+        .collect())
+}
+
+fn sessions() -> Result<Vec<(PathBuf, bool)>> {
+    let dir = get_multishell_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut sessions = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let entry = entry?;
+        let Some(pid) = entry.file_name().to_str().and_then(parse_session_pid) else {
+            continue;
+        };
+
+        let expired = entry
+            .path()
+            .symlink_metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > STALE_SESSION_AGE);
+
+        let stale = if cfg!(unix) {
+            !crate::utils::is_process_alive(pid)
+        } else {
+            expired
+        };
+
+        sessions.push((entry.path(), !stale));
+    }
+
+    Ok(sessions)
+}
+
+fn parse_session_pid(name: &str) -> Option<u32> {
+    let (pid, timestamp) = name.split_once('_')?;
+    timestamp.parse::<u128>().ok()?;
+    pid.parse().ok()
+}
+
+#[cfg(unix)]
+fn parent_pid() -> u32 {
+    std::os::unix::process::parent_id()
+}
+
+#[cfg(not(unix))]
+fn parent_pid() -> u32 {
+    std::process::id()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_session_pid() {
+        assert_eq!(parse_session_pid("4242_1700000000000"), Some(4242));
+        assert_eq!(parse_session_pid("4242"), None);
+        assert_eq!(parse_session_pid("abc_1700000000000"), None);
+        assert_eq!(parse_session_pid("4242_abc"), None);
+    }
+}
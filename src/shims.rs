@@ -0,0 +1,184 @@
+use crate::errors::{NodeError, Result};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Binaries that always get a shim, whether or not the installed versions ship them yet.
+pub const SHIMMED_BINARIES: &[&str] = &["node", "npm", "npx", "corepack", "yarn", "pnpm"];
+
+pub fn get_shims_dir() -> PathBuf {
+    crate::utils::get_base_dir().join("shims")
+}
+
+/// Returns the binary name when this process was started through a shim rather than as `rnvm`.
+#[cfg(unix)]
+pub fn invoked_shim() -> Option<String> {
+    // Shims are symlinks to rnvm, so only argv[0] still carries the shim's name.
+    let argv0 = PathBuf::from(std::env::args_os().next()?);
+    let name = shim_name(&argv0)?;
+    fs::symlink_metadata(get_shims_dir().join(argv0.file_name()?))
+        .ok()
+        .map(|_| name)
+}
+
+/// Returns the binary name when this process was started through a shim rather than as `rnvm`.
+#[cfg(windows)]
+pub fn invoked_shim() -> Option<String> {
+    // argv[0] is whatever was typed (`node`, without `.exe`), so ask for the real path instead.
+    let exe = std::env::current_exe().ok()?;
+    let in_shims_dir = match (exe.parent().map(fs::canonicalize), fs::canonicalize(get_shims_dir())) {
+        (Some(Ok(parent)), Ok(shims_dir)) => parent == shims_dir,
+        _ => false,
+    };
+    if in_shims_dir { shim_name(&exe) } else { None }
+}
+
+fn shim_name(argv0: &Path) -> Option<String> {
+    let name = if cfg!(windows) { argv0.file_stem() } else { argv0.file_name() }?.to_str()?;
+    (!name.eq_ignore_ascii_case("rnvm")).then(|| name.to_string())
+}
+
+/// Runs `name` from the version the current directory asks for, falling back to the `default` alias.
+pub fn dispatch(name: &str) -> Result<()> {
+    let version = resolve_shim_version()?;
+    let bin_dir = crate::version::local::get_bin_dir(&crate::version::local::get_versions_dir().join(&version));
+    let program = find_binary(&bin_dir, name).ok_or_else(|| {
+        NodeError::SystemError(format!(
+            "{} is not available in Node.js {}",
+            name, version
+        ))
+    })?;
+
+    crate::exec::run_with_version(&version, &program.to_string_lossy(), std::env::args_os().skip(1))
+}
+
+fn resolve_shim_version() -> Result<String> {
+    let spec = match crate::config::local::find_version_file() {
+        Some(found) => found.version,
+        None => crate::config::alias::get_alias("default")?.ok_or_else(|| {
+            NodeError::ConfigError(
+                "No version file found and no default version set. Run 'rnvm use <version> --default'.".to_string(),
+            )
+        })?,
+    };
+
+    crate::version::find_installed_version(&spec)?
+        .map(|v| v.version_str())
+        .ok_or_else(|| {
+            NodeError::VersionNotInstalled(format!(
+                "No installed version matches '{}'. Run 'rnvm install {}' first.",
+                spec, spec
+            ))
+        })
+}
+
+fn find_binary(bin_dir: &Path, name: &str) -> Option<PathBuf> {
+    if !cfg!(windows) {
+        let path = bin_dir.join(name);
+        return path.is_file().then_some(path);
+    }
+
+    // Appended rather than set, so dotted names like `foo.js` keep their suffix.
+    ["exe", "cmd"]
+        .iter()
+        .map(|ext| bin_dir.join(format!("{}.{}", name, ext)))
+        .find(|path| path.is_file())
+}
+
+/// Recreates the shims directory: the standard binaries plus every executable found in the
+/// installed versions, so globally installed package binaries are reachable too.
+pub fn rebuild() -> Result<Vec<String>> {
+    let shims_dir = get_shims_dir();
+    if shims_dir.exists() {
+        fs::remove_dir_all(&shims_dir)?;
+    }
+    fs::create_dir_all(&shims_dir)?;
+
+    let mut names: BTreeSet<String> = SHIMMED_BINARIES.iter().map(|s| s.to_string()).collect();
+    for installed in crate::version::local::get_installed_versions()? {
+        names.extend(list_binaries(&crate::version::local::get_bin_dir(&installed.path)));
+    }
+
+    let rnvm = std::env::current_exe()?;
+    for name in &names {
+        create_shim(&rnvm, &shims_dir, name)?;
+    }
+    Ok(names.into_iter().collect())
+}
+
+fn list_binaries(bin_dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(bin_dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| {
+            let path = entry.path();
+            if cfg!(windows) {
+                let ext = path.extension()?.to_str()?.to_lowercase();
+                matches!(ext.as_str(), "exe" | "cmd").then(|| path.file_stem()?.to_str().map(str::to_string))?
+            } else {
+                path.file_name()?.to_str().map(str::to_string)
+            }
+        })
+        .filter(|name| shim_name(Path::new(name)).is_some())
+        .collect()
+}
+
+#[cfg(unix)]
+fn create_shim(rnvm: &Path, shims_dir: &Path, name: &str) -> Result<()> {
+    std::os::unix::fs::symlink(rnvm, shims_dir.join(name))?;
+    Ok(())
+}
+
+#[cfg(windows)]
+fn create_shim(rnvm: &Path, shims_dir: &Path, name: &str) -> Result<()> {
+    let shim = shims_dir.join(format!("{}.exe", name));
+    if fs::hard_link(rnvm, &shim).is_err() {
+        fs::copy(rnvm, &shim)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shim_name() {
+        assert_eq!(shim_name(Path::new("/home/user/.rnvm/shims/node")), Some("node".to_string()));
+        assert_eq!(shim_name(Path::new("npx")), Some("npx".to_string()));
+        assert_eq!(shim_name(Path::new("/usr/local/bin/rnvm")), None);
+        if cfg!(windows) {
+            assert_eq!(shim_name(Path::new("RNVM.exe")), None);
+        } else {
+            assert_eq!(shim_name(Path::new("/home/user/.rnvm/shims/foo.js")), Some("foo.js".to_string()));
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_binary_keeps_dotted_names() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("foo.js"), "").unwrap();
+
+        assert_eq!(find_binary(temp_dir.path(), "foo.js"), Some(temp_dir.path().join("foo.js")));
+        assert_eq!(find_binary(temp_dir.path(), "foo"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_list_binaries() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("node"), "").unwrap();
+        fs::write(temp_dir.path().join("tsc"), "").unwrap();
+        fs::create_dir(temp_dir.path().join("lib")).unwrap();
+
+        let mut binaries = list_binaries(temp_dir.path());
+        binaries.sort();
+        assert_eq!(binaries, vec!["node", "tsc"]);
+        assert!(list_binaries(&temp_dir.path().join("missing")).is_empty());
+    }
+}
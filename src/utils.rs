@@ -2,7 +2,27 @@ use std::path::PathBuf;
 use std::error::Error;
 use std::fs;
 
+#[cfg(test)]
+thread_local! {
+    /// Points `get_base_dir` at a temp dir for the current test without touching the environment.
+    pub static TEST_BASE_DIR: std::cell::RefCell<Option<PathBuf>> = const { std::cell::RefCell::new(None) };
+}
+
+/// `$RNVM_DIR` when set, otherwise `~/.rnvm`.
 pub fn get_base_dir() -> PathBuf {
+    #[cfg(test)]
+    if let Some(dir) = TEST_BASE_DIR.with(|dir| dir.borrow().clone()) {
+        return dir;
+    }
+
+    match std::env::var_os("RNVM_DIR") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => get_home_dir().join(".rnvm"),
+    }
+}
+
+/// The user's home directory, which also holds the config of other version managers and shells.
+pub fn get_home_dir() -> PathBuf {
     let home = if cfg!(windows) {
         std::env::var("USERPROFILE").unwrap_or_else(|_| {
             std::env::var("HOMEDRIVE").unwrap_or_default() +
@@ -12,7 +32,7 @@ pub fn get_base_dir() -> PathBuf {
         std::env::var("HOME").unwrap_or_else(|_| ".".into())
     };
 
-    PathBuf::from(home).join(".rnvm")
+    PathBuf::from(home)
 }
 
 pub fn ensure_base_dir() -> Result<PathBuf, Box<dyn Error>> {
@@ -57,4 +77,20 @@ pub fn check_permissions(path: &PathBuf) -> Result<(), Box<dyn Error>> {
             e
         ).into())
     }
+}
+
+/// Best-effort check whether a process with `pid` is still running.
+#[cfg(unix)]
+pub fn is_process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // Signal 0 only checks for existence; EPERM means it exists but belongs to someone else.
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+pub fn is_process_alive(_pid: u32) -> bool {
+    true
 }
\ No newline at end of file
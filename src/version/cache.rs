@@ -0,0 +1,214 @@
+use crate::errors::{NodeError, Result};
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DEFAULT_INDEX_TTL_SECS: u64 = 60 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheMeta {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    fetched_at: u64,
+}
+
+impl CacheMeta {
+    fn is_fresh(&self, url: &str, now: u64, ttl: u64) -> bool {
+        self.url == url && now.saturating_sub(self.fetched_at) < ttl
+    }
+}
+
+/// The index and its metadata live in one file, so they are always replaced together.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedIndex {
+    meta: CacheMeta,
+    content: String,
+}
+
+pub fn get_cache_dir() -> PathBuf {
+    crate::utils::get_base_dir().join("cache")
+}
+
+fn get_cache_file() -> PathBuf {
+    get_cache_dir().join("index.cache.json")
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn load_cache() -> Option<CachedIndex> {
+    let content = fs::read_to_string(get_cache_file()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Writes through a temp file in the cache dir, so a concurrent reader never sees a partial file.
+fn save_cache(cache: &CachedIndex) -> Result<()> {
+    let content = serde_json::to_string(cache)
+        .map_err(|e| NodeError::ConfigError(format!("Failed to serialize the index cache: {}", e)))?;
+
+    fs::create_dir_all(get_cache_dir())?;
+    let mut file = tempfile::NamedTempFile::new_in(get_cache_dir())?;
+    file.write_all(content.as_bytes())?;
+    file.persist(get_cache_file()).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// The cache, as long as it was fetched from the mirror currently in use.
+fn load_cache_for(url: &str) -> Option<CachedIndex> {
+    load_cache().filter(|cache| cache.meta.url == url)
+}
+
+/// The cached index, as long as it was fetched from the mirror currently in use.
+pub fn read_cached_index() -> Option<String> {
+    load_cache_for(&index_url().ok()?).map(|cache| cache.content)
+}
+
+/// Returns the contents of `index.json`, served from the cache while it is within the TTL
+/// and revalidated with a conditional GET once it has expired.
+pub async fn load_index() -> Result<String> {
+    let url = index_url()?;
+    let cache = load_cache_for(&url);
+
+    if crate::config::settings::offline() {
+        return cache.map(|cache| cache.content).ok_or_else(|| {
+            NodeError::OfflineError(
+                format!("No cached version index for {} is available. Run 'rnvm cache refresh' while online.", url),
+            )
+        });
+    }
+
+    let ttl = crate::config::settings::load_settings()?
+        .index_cache_ttl
+        .unwrap_or(DEFAULT_INDEX_TTL_SECS);
+    if let Some(cache) = &cache {
+        if cache.meta.is_fresh(&url, now(), ttl) {
+            return Ok(cache.content.clone());
+        }
+    }
+
+    match fetch_index(&url, cache.as_ref()).await {
+        Ok(content) => Ok(content),
+        Err(e) => match cache {
+            Some(cache) => {
+                eprintln!("Warning: using cached version index ({})", e);
+                Ok(cache.content)
+            }
+            None => Err(e),
+        },
+    }
+}
+
+/// Revalidates the cached index regardless of its age.
+pub async fn refresh() -> Result<()> {
+    crate::version::remote::ensure_online("Refreshing the version index")?;
+    let url = index_url()?;
+    fetch_index(&url, load_cache_for(&url).as_ref()).await?;
+    Ok(())
+}
+
+pub fn clear() -> Result<()> {
+    let cache_dir = get_cache_dir();
+    if cache_dir.exists() {
+        fs::remove_dir_all(&cache_dir)?;
+    }
+    Ok(())
+}
+
+fn index_url() -> Result<String> {
+    Ok(format!("{}/index.json", crate::version::remote::get_dist_url()?))
+}
+
+async fn fetch_index(url: &str, cache: Option<&CachedIndex>) -> Result<String> {
+    let dist_url = crate::version::remote::get_dist_url()?;
+    let mut request = crate::version::remote::authorize(Client::new().get(url), &dist_url);
+
+    if let Some(cache) = cache {
+        if let Some(etag) = &cache.meta.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &cache.meta.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| NodeError::DownloadError(e.to_string()))?;
+
+    if response.status() == StatusCode::NOT_MODIFIED {
+        if let Some(cache) = cache {
+            save_cache(&CachedIndex {
+                meta: CacheMeta {
+                    fetched_at: now(),
+                    ..cache.meta.clone()
+                },
+                content: cache.content.clone(),
+            })?;
+            return Ok(cache.content.clone());
+        }
+    }
+
+    if !response.status().is_success() {
+        return Err(NodeError::DownloadError(format!(
+            "Failed to fetch {}: HTTP {}",
+            url,
+            response.status()
+        )));
+    }
+
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string())
+    };
+    let meta = CacheMeta {
+        url: url.to_string(),
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+        fetched_at: now(),
+    };
+
+    let content = response
+        .text()
+        .await
+        .map_err(|e| NodeError::DownloadError(e.to_string()))?;
+
+    // A mirror may answer 200 with a login page; never cache something that is not an index.
+    crate::version::remote::parse_version_list(&content)
+        .map_err(|e| NodeError::DownloadError(format!("{} did not return a version index ({})", url, e)))?;
+
+    save_cache(&CachedIndex { meta, content: content.clone() })?;
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_freshness() {
+        let meta = CacheMeta {
+            url: "https://nodejs.org/dist/index.json".to_string(),
+            etag: None,
+            last_modified: None,
+            fetched_at: 1_000,
+        };
+
+        assert!(meta.is_fresh("https://nodejs.org/dist/index.json", 1_500, 3_600));
+        assert!(!meta.is_fresh("https://nodejs.org/dist/index.json", 4_600, 3_600));
+        assert!(!meta.is_fresh("https://mirror.example.com/index.json", 1_500, 3_600));
+        assert!(!meta.is_fresh("https://nodejs.org/dist/index.json", 1_000, 0));
+    }
+}
@@ -1,4 +1,5 @@
-use semver::{Version, VersionReq};
+use crate::errors::{NodeError, Result};
+use semver::{Prerelease, Version};
 use std::cmp::Ordering;
 
 pub fn compare_versions(a: &str, b: &str) -> Ordering {
@@ -8,13 +9,239 @@ pub fn compare_versions(a: &str, b: &str) -> Ordering {
 }
 
 pub fn matches_requirement(version: &str, requirement: &str) -> bool {
-    if let (Ok(ver), Ok(req)) = (Version::parse(version), VersionReq::parse(requirement)) {
-        req.matches(&ver)
+    if let (Ok(ver), Ok(range)) = (Version::parse(version), Range::parse(requirement)) {
+        range.matches(&ver)
     } else {
         false
     }
 }
 
+/// An npm-style range such as `>=14.0.0`, `^18 || ^20`, `16 - 18.2` or `20.x`.
+///
+/// Each `||` alternative is a set of comparators that must all hold. Prerelease handling is
+/// simplified to plain ordering, since Node.js releases never carry prerelease tags.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Range {
+    sets: Vec<Vec<Comparator>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Comparator {
+    op: Op,
+    version: Version,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl Comparator {
+    fn new(op: Op, version: Version) -> Self {
+        Self { op, version }
+    }
+
+    /// A comparator nothing satisfies, used for ranges like `<*`.
+    fn none() -> Self {
+        Self::new(Op::Lt, floor(0, 0, 0))
+    }
+
+    fn matches(&self, version: &Version) -> bool {
+        let ordering = version.cmp(&self.version);
+        match self.op {
+            Op::Eq => ordering == Ordering::Equal,
+            Op::Gt => ordering == Ordering::Greater,
+            Op::Ge => ordering != Ordering::Less,
+            Op::Lt => ordering == Ordering::Less,
+            Op::Le => ordering != Ordering::Greater,
+        }
+    }
+}
+
+/// A version where any component may be missing or a wildcard (`x`, `X`, `*`).
+struct Partial {
+    major: Option<u64>,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    pre: Prerelease,
+}
+
+impl Partial {
+    fn parse(input: &str) -> Option<Self> {
+        let input = input.trim_start_matches(['v', 'V', '=']);
+        let input = input.split('+').next()?;
+        let (numbers, pre) = match input.split_once('-') {
+            Some((numbers, pre)) => (numbers, Prerelease::new(pre).ok()?),
+            None => (input, Prerelease::EMPTY),
+        };
+
+        let mut parts = Vec::new();
+        for part in numbers.split('.') {
+            match part {
+                "x" | "X" | "*" => parts.push(None),
+                _ => parts.push(Some(part.parse::<u64>().ok()?)),
+            }
+        }
+        if parts.len() > 3 {
+            return None;
+        }
+        parts.resize(3, None);
+
+        // Anything after a wildcard is a wildcard too: `1.x.3` means `1.x`.
+        let major = parts[0];
+        let minor = major.and(parts[1]);
+        let patch = minor.and(parts[2]);
+        Some(Self { major, minor, patch, pre })
+    }
+
+    fn is_full(&self) -> bool {
+        self.patch.is_some()
+    }
+
+    /// The lowest version the partial covers, with missing components as zero.
+    fn lower(&self) -> Version {
+        Version {
+            pre: self.pre.clone(),
+            ..Version::new(self.major.unwrap_or(0), self.minor.unwrap_or(0), self.patch.unwrap_or(0))
+        }
+    }
+
+    /// The first version past everything the partial covers, e.g. `2.0.0-0` for `1` or `1.3.0-0` for `1.2`.
+    fn upper(&self) -> Option<Version> {
+        match (self.major, self.minor) {
+            (None, _) => None,
+            (Some(major), None) => Some(floor(major + 1, 0, 0)),
+            (Some(major), Some(minor)) => Some(floor(major, minor + 1, 0)),
+        }
+    }
+
+    fn x_range(&self) -> Vec<Comparator> {
+        match self.upper() {
+            _ if self.is_full() => vec![Comparator::new(Op::Eq, self.lower())],
+            Some(upper) => vec![Comparator::new(Op::Ge, self.lower()), Comparator::new(Op::Lt, upper)],
+            None => Vec::new(),
+        }
+    }
+}
+
+/// The lowest possible version of `major.minor.patch`, below all of its prereleases.
+fn floor(major: u64, minor: u64, patch: u64) -> Version {
+    Version {
+        pre: Prerelease::new("0").unwrap(),
+        ..Version::new(major, minor, patch)
+    }
+}
+
+impl Range {
+    pub fn parse(range: &str) -> Result<Self> {
+        let invalid = || NodeError::InvalidVersion(format!("Invalid version range: {}", range));
+
+        let mut sets = Vec::new();
+        for set in range.split("||") {
+            sets.push(parse_set(set.trim()).ok_or_else(invalid)?);
+        }
+        Ok(Self { sets })
+    }
+
+    pub fn matches(&self, version: &Version) -> bool {
+        self.sets
+            .iter()
+            .any(|set| set.iter().all(|comparator| comparator.matches(version)))
+    }
+}
+
+fn parse_set(set: &str) -> Option<Vec<Comparator>> {
+    let tokens: Vec<&str> = set.split_whitespace().collect();
+
+    if let [from, "-", to] = tokens.as_slice() {
+        let from = Partial::parse(from)?;
+        let to = Partial::parse(to)?;
+        let mut comparators = vec![Comparator::new(Op::Ge, from.lower())];
+        if to.is_full() {
+            comparators.push(Comparator::new(Op::Le, to.lower()));
+        } else if let Some(upper) = to.upper() {
+            comparators.push(Comparator::new(Op::Lt, upper));
+        }
+        return Some(comparators);
+    }
+
+    // Allow a space between an operator and its version, as in `>= 14`.
+    let mut merged: Vec<String> = Vec::new();
+    let mut pending = String::new();
+    for token in tokens {
+        pending.push_str(token);
+        if !token.chars().all(|c| matches!(c, '<' | '>' | '=' | '~' | '^')) {
+            merged.push(std::mem::take(&mut pending));
+        }
+    }
+    if !pending.is_empty() {
+        return None;
+    }
+
+    let mut comparators = Vec::new();
+    for token in merged {
+        comparators.extend(parse_comparator(&token)?);
+    }
+    Some(comparators)
+}
+
+fn parse_comparator(token: &str) -> Option<Vec<Comparator>> {
+    let (op, version) = ["~>", ">=", "<=", ">", "<", "~", "^", "="]
+        .iter()
+        .find_map(|op| token.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("", token));
+    let partial = Partial::parse(version)?;
+
+    let Some(major) = partial.major else {
+        // `*`, `>=*` and `<=*` match everything; `>*` and `<*` match nothing.
+        return Some(match op {
+            ">" | "<" => vec![Comparator::none()],
+            _ => Vec::new(),
+        });
+    };
+
+    let comparators = match op {
+        "" | "=" => partial.x_range(),
+        "~" | "~>" => {
+            let upper = match partial.minor {
+                Some(minor) => floor(major, minor + 1, 0),
+                None => floor(major + 1, 0, 0),
+            };
+            vec![Comparator::new(Op::Ge, partial.lower()), Comparator::new(Op::Lt, upper)]
+        }
+        "^" => {
+            // Caret allows changes that keep the left-most non-zero component.
+            let upper = match (partial.minor, partial.patch) {
+                _ if major > 0 => floor(major + 1, 0, 0),
+                (None, _) => floor(1, 0, 0),
+                (Some(minor), _) if minor > 0 => floor(0, minor + 1, 0),
+                (Some(_), None) => floor(0, 1, 0),
+                (Some(_), Some(patch)) => floor(0, 0, patch + 1),
+            };
+            vec![Comparator::new(Op::Ge, partial.lower()), Comparator::new(Op::Lt, upper)]
+        }
+        ">" if partial.is_full() => vec![Comparator::new(Op::Gt, partial.lower())],
+        ">" => {
+            let next = match partial.minor {
+                Some(minor) => Version::new(major, minor + 1, 0),
+                None => Version::new(major + 1, 0, 0),
+            };
+            vec![Comparator::new(Op::Ge, next)]
+        }
+        ">=" => vec![Comparator::new(Op::Ge, partial.lower())],
+        "<" if partial.is_full() => vec![Comparator::new(Op::Lt, partial.lower())],
+        "<" => vec![Comparator::new(Op::Lt, floor(major, partial.minor.unwrap_or(0), 0))],
+        "<=" if partial.is_full() => vec![Comparator::new(Op::Le, partial.lower())],
+        "<=" => vec![Comparator::new(Op::Lt, partial.upper()?)],
+        _ => return None,
+    };
+    Some(comparators)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -32,4 +259,50 @@ mod tests {
         assert!(matches_requirement("2.0.0", ">=1.0.0"));
         assert!(!matches_requirement("0.9.0", "^1.0.0"));
     }
+
+    #[test]
+    fn test_npm_ranges() {
+        let cases = [
+            // (range, matching, not matching)
+            (">=14.0.0", &["14.0.0", "22.1.0"][..], &["13.14.0"][..]),
+            (">= 14", &["14.0.0"], &["12.22.12"]),
+            ("^18 || ^20", &["18.0.0", "20.11.1"], &["19.9.0", "21.0.0", "16.20.2"]),
+            ("16 - 18.2", &["16.0.0", "18.2.9"], &["15.9.9", "18.3.0"]),
+            ("16.1 - 18.2.0", &["16.1.0", "18.2.0"], &["16.0.9", "18.2.1"]),
+            ("20.x", &["20.0.0", "20.11.1"], &["21.0.0", "19.9.9"]),
+            ("20.X.x", &["20.11.1"], &["21.0.0"]),
+            ("18", &["18.19.1"], &["19.0.0"]),
+            ("18.19", &["18.19.1"], &["18.20.0"]),
+            ("v20.11.1", &["20.11.1"], &["20.11.0"]),
+            ("*", &["0.10.48", "22.0.0"], &[]),
+            ("", &["22.0.0"], &[]),
+            ("~18.2.1", &["18.2.1", "18.2.9"], &["18.3.0", "18.2.0"]),
+            ("~18", &["18.20.0"], &["19.0.0"]),
+            ("^0.12.3", &["0.12.18"], &["0.13.0"]),
+            ("^0.0.3", &["0.0.3"], &["0.0.4"]),
+            (">18", &["19.0.0"], &["18.20.0"]),
+            (">18.2.0", &["18.2.1"], &["18.2.0"]),
+            ("<18", &["17.9.9"], &["18.0.0"]),
+            ("<=18", &["18.20.0"], &["19.0.0"]),
+            (">=16 <20", &["16.0.0", "19.9.9"], &["20.0.0", "15.0.0"]),
+            ("<*", &[], &["1.0.0"]),
+        ];
+
+        for (range, matching, not_matching) in cases {
+            let parsed = Range::parse(range).unwrap();
+            for version in matching {
+                assert!(parsed.matches(&Version::parse(version).unwrap()), "{} should match {}", range, version);
+            }
+            for version in not_matching {
+                assert!(!parsed.matches(&Version::parse(version).unwrap()), "{} should not match {}", range, version);
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_ranges() {
+        for range in ["abc", ">=", "1.2.3.4", "^1 ||| 2", "1 - "] {
+            assert!(Range::parse(range).is_err(), "{} should be invalid", range);
+        }
+    }
 }
\ No newline at end of file
@@ -19,6 +19,19 @@ pub fn get_versions_dir() -> PathBuf {
     crate::utils::get_base_dir().join("versions")
 }
 
+/// Directory holding the `node` executable inside an installed version.
+pub fn get_bin_dir(version_dir: &Path) -> PathBuf {
+    if cfg!(windows) {
+        version_dir.to_path_buf()
+    } else {
+        version_dir.join("bin")
+    }
+}
+
+pub fn get_node_binary(version_dir: &Path) -> PathBuf {
+    get_bin_dir(version_dir).join(if cfg!(windows) { "node.exe" } else { "node" })
+}
+
 pub fn is_empty() -> bool {
     let versions_dir = get_versions_dir();
     if !versions_dir.exists() {
@@ -31,7 +44,7 @@ pub fn is_empty() -> bool {
                 if let Ok(entry) = entry {
                     if entry.path().is_dir() {
                         if let Some(name) = entry.file_name().to_str() {
-                            if name != "downloads" {
+                            if name != "downloads" && !name.starts_with(crate::installer::extract::STAGING_PREFIX) {
                                 return false;
                             }
                         }
@@ -44,18 +57,39 @@ pub fn is_empty() -> bool {
     true
 }
 
+/// Only complete installs count: the dir must carry an install manifest.
 pub fn is_installed(version: &str) -> Result<bool> {
-    let version_dir = get_versions_dir().join(version);
-    Ok(version_dir.exists() && version_dir.is_dir())
+    Ok(is_complete(&get_versions_dir().join(version)))
+}
+
+fn is_complete(version_dir: &Path) -> bool {
+    version_dir.is_dir() && crate::installer::manifest::read_manifest(version_dir).is_some()
+}
+
+/// The global `current` link, used by shells that were not set up with `rnvm env`.
+pub fn get_global_link() -> PathBuf {
+    crate::utils::get_base_dir().join("current")
+}
+
+/// The link `use` switches: this shell's multishell link when `RNVM_MULTISHELL_PATH` is set,
+/// otherwise the global `current` link.
+pub fn get_current_link() -> PathBuf {
+    match std::env::var_os("RNVM_MULTISHELL_PATH") {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => get_global_link(),
+    }
 }
 
 pub fn get_current_version() -> Result<Option<String>> {
-    let current_link = crate::utils::get_base_dir().join("current");
-    if !current_link.exists() {
+    read_link_version(&get_current_link())
+}
+
+pub fn read_link_version(link: &Path) -> Result<Option<String>> {
+    if !link.exists() {
         return Ok(None);
     }
 
-    let target = fs::read_link(&current_link)?;
+    let target = fs::read_link(link)?;
     Ok(target
         .file_name()
         .and_then(|n| n.to_str())
@@ -63,26 +97,53 @@ pub fn get_current_version() -> Result<Option<String>> {
 }
 
 pub fn use_version(version: &str) -> Result<()> {
-    let versions_dir = get_versions_dir();
-    let version_dir = versions_dir.join(version);
+    link_version(version, &get_current_link())
+}
 
-    if !version_dir.exists() {
+/// Points the global `current` link at `version`, making it the version new shells start with.
+pub fn set_global_version(version: &str) -> Result<()> {
+    link_version(version, &get_global_link())
+}
+
+fn link_version(version: &str, link: &Path) -> Result<()> {
+    let version_dir = get_versions_dir().join(version);
+
+    if !is_installed(version)? {
         return Err(NodeError::VersionNotInstalled(format!(
             "Version {} is not installed. Use 'rnvm install {}' first.",
             version, version
         )));
     }
 
-    let current_link = crate::utils::get_base_dir().join("current");
-    if current_link.exists() {
-        fs::remove_file(&current_link)?;
+    let _lock = crate::lock::lock_config()?;
+    replace_link(&version_dir, link)
+}
+
+/// Replaces `link` with a symlink to `target`, also when the old link is dangling.
+pub fn replace_link(target: &Path, link: &Path) -> Result<()> {
+    if fs::symlink_metadata(link).is_ok() {
+        remove_link(link)?;
+    }
+    if let Some(parent) = link.parent() {
+        fs::create_dir_all(parent)?;
     }
 
-    symlink_version(&version_dir, &current_link)?;
+    symlink_version(target, link)?;
     Ok(())
 }
 
-pub fn remove_version(version: &str) -> Result<()> {
+pub fn remove_link(link: &Path) -> Result<()> {
+    // Directory symlinks on Windows have to be removed as directories.
+    if fs::remove_file(link).is_err() {
+        fs::remove_dir(link)?;
+    }
+    Ok(())
+}
+
+/// Removes `version` unless `current_link` (normally [`get_current_link`]), the global link or a
+/// live shell session still points to it.
+pub fn remove_version(version: &str, current_link: &Path) -> Result<()> {
+    let _lock = crate::lock::lock_version(version)?;
     let version_dir = get_versions_dir().join(version);
     if !version_dir.exists() {
         return Err(NodeError::VersionNotInstalled(format!(
@@ -91,12 +152,24 @@ pub fn remove_version(version: &str) -> Result<()> {
         )));
     }
 
-    // Check if it's the current version
-    if let Some(current) = get_current_version()? {
-        if current == version {
-            return Err(NodeError::VersionNotInstalled(
-                "Cannot remove the currently active version. Switch to a different version first.".to_string()
-            ));
+    // Refuse while this shell, the global link or another live session still points here
+    if read_link_version(current_link)?.as_deref() == Some(version) {
+        return Err(NodeError::ConfigError(
+            "Cannot remove the currently active version. Switch to a different version first.".to_string()
+        ));
+    }
+    if read_link_version(&get_global_link())?.as_deref() == Some(version) {
+        return Err(NodeError::ConfigError(format!(
+            "Cannot remove {}: it is the global version new shells start with. Run 'rnvm use --default <version>' first.",
+            version
+        )));
+    }
+    for session in crate::shell::multishell::live_sessions()? {
+        if read_link_version(&session)?.as_deref() == Some(version) {
+            return Err(NodeError::ConfigError(format!(
+                "Cannot remove {}: it is active in another shell session. Switch that shell to a different version first.",
+                version
+            )));
         }
     }
 
@@ -123,6 +196,10 @@ pub fn get_installed_versions() -> Result<Vec<LocalVersion>> {
                     continue;
                 }
 
+                if !is_complete(&path) {
+                    continue;
+                }
+
                 if let Ok(version) = Version::parse(version_str) {
                     versions.push(LocalVersion {
                         version,
@@ -138,6 +215,76 @@ pub fn get_installed_versions() -> Result<Vec<LocalVersion>> {
     Ok(versions)
 }
 
+/// Version dirs left without an install manifest, e.g. by an interrupted install.
+pub fn get_broken_versions() -> Result<Vec<String>> {
+    let versions_dir = get_versions_dir();
+    if !versions_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut broken = Vec::new();
+    for entry in fs::read_dir(&versions_dir)? {
+        let path = entry?.path();
+        let Some(version) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if version.starts_with(crate::installer::extract::STAGING_PREFIX) {
+            continue;
+        }
+        if path.is_dir() && Version::parse(version).is_ok() && !is_complete(&path) {
+            broken.push(version.to_string());
+        }
+    }
+
+    broken.sort_by(|a, b| crate::version::compare::compare_versions(a, b));
+    Ok(broken)
+}
+
+/// Writes a manifest for version dirs installed by an rnvm that predates manifests, as long as
+/// they ship npm and their `node` reports the version the dir is named after. Returns the
+/// versions adopted; anything else stays broken for `rnvm install --repair`.
+pub fn adopt_legacy_versions() -> Result<Vec<String>> {
+    let mut adopted = Vec::new();
+    for version in get_broken_versions()? {
+        let version_dir = get_versions_dir().join(&version);
+        if !has_npm(&version_dir) || !reports_version(&get_node_binary(&version_dir), &version) {
+            continue;
+        }
+
+        let _lock = crate::lock::lock_version(&version)?;
+        if is_complete(&version_dir) {
+            continue;
+        }
+        let manifest = crate::installer::manifest::InstallManifest::new(
+            crate::installer::manifest::LEGACY_SOURCE,
+            None,
+            &version_dir,
+        )?;
+        crate::installer::manifest::write_manifest(&version_dir, &manifest)?;
+        adopted.push(version);
+    }
+    Ok(adopted)
+}
+
+/// npm is extracted after `node` itself, so a half-extracted install usually lacks it.
+fn has_npm(version_dir: &Path) -> bool {
+    let (npm, modules) = if cfg!(windows) {
+        (version_dir.join("npm.cmd"), version_dir.join("node_modules"))
+    } else {
+        (version_dir.join("bin").join("npm"), version_dir.join("lib").join("node_modules"))
+    };
+    npm.exists() && modules.join("npm").join("package.json").is_file()
+}
+
+fn reports_version(node: &Path, version: &str) -> bool {
+    std::process::Command::new(node)
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| {
+            output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == format!("v{}", version)
+        })
+}
+
 #[cfg(unix)]
 fn symlink_version(version_dir: &Path, link: &Path) -> Result<()> {
     std::os::unix::fs::symlink(version_dir, link)?;
@@ -153,17 +300,35 @@ fn symlink_version(version_dir: &Path, link: &Path) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::installer::manifest::{write_manifest, InstallManifest};
     use std::fs;
     use tempfile;
 
     fn setup_test_env() -> tempfile::TempDir {
         let temp_dir = tempfile::tempdir().unwrap();
-        std::env::set_var("HOME", temp_dir.path());
-        let versions_dir = temp_dir.path().join(".nrvm").join("versions");
-        fs::create_dir_all(&versions_dir).unwrap();
+        let base_dir = temp_dir.path().join(".rnvm");
+        crate::utils::TEST_BASE_DIR.with(|dir| *dir.borrow_mut() = Some(base_dir.clone()));
+        fs::create_dir_all(base_dir.join("versions")).unwrap();
         temp_dir
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_replace_link() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let first = temp_dir.path().join("18.19.1");
+        let second = temp_dir.path().join("20.11.1");
+        fs::create_dir_all(&second).unwrap();
+        let link = temp_dir.path().join("multishells").join("1_1");
+
+        // A dangling link must be replaced too.
+        replace_link(&first, &link).unwrap();
+        assert_eq!(read_link_version(&link).unwrap(), None);
+
+        replace_link(&second, &link).unwrap();
+        assert_eq!(read_link_version(&link).unwrap(), Some("20.11.1".to_string()));
+    }
+
     #[test]
     fn test_version_management() {
         let temp_dir = setup_test_env();
@@ -172,9 +337,20 @@ mod tests {
         // Test empty state
         assert!(is_empty());
 
+        // Staging dirs of running installs are not versions
+        fs::create_dir_all(versions_dir.join(".staging-4242-abc")).unwrap();
+        assert!(is_empty());
+
         // Create test versions
-        fs::create_dir_all(versions_dir.join("14.0.0")).unwrap();
-        fs::create_dir_all(versions_dir.join("16.0.0")).unwrap();
+        for version in ["14.0.0", "16.0.0"] {
+            let version_dir = versions_dir.join(version);
+            fs::create_dir_all(&version_dir).unwrap();
+            let manifest = InstallManifest::new("https://nodejs.org/dist", None, &version_dir).unwrap();
+            write_manifest(&version_dir, &manifest).unwrap();
+        }
+
+        // An interrupted install is neither listed nor installed
+        fs::create_dir_all(versions_dir.join("18.0.0")).unwrap();
 
         // Test non-empty state
         assert!(!is_empty());
@@ -183,6 +359,7 @@ mod tests {
         assert!(is_installed("14.0.0").unwrap());
         assert!(is_installed("16.0.0").unwrap());
         assert!(!is_installed("18.0.0").unwrap());
+        assert_eq!(get_broken_versions().unwrap(), vec!["18.0.0"]);
 
         // Test version listing
         let versions = get_installed_versions().unwrap();
@@ -190,15 +367,22 @@ mod tests {
         assert_eq!(versions[0].version_str(), "16.0.0");
         assert_eq!(versions[1].version_str(), "14.0.0");
 
-        // Test version switching
-        use_version("14.0.0").unwrap();
-        assert_eq!(get_current_version().unwrap(), Some("14.0.0".to_string()));
+        // Test version switching, on a session link of our own rather than an inherited
+        // RNVM_MULTISHELL_PATH
+        let link = temp_dir.path().join("session");
+        link_version("14.0.0", &link).unwrap();
+        assert_eq!(read_link_version(&link).unwrap(), Some("14.0.0".to_string()));
 
         // Test version removal
-        use_version("16.0.0").unwrap();
-        remove_version("14.0.0").unwrap();
+        assert!(remove_version("14.0.0", &link).is_err());
+        link_version("16.0.0", &link).unwrap();
+        remove_version("14.0.0", &link).unwrap();
         assert!(!is_installed("14.0.0").unwrap());
 
+        // The global link protects its version too
+        link_version("16.0.0", &get_global_link()).unwrap();
+        assert!(remove_version("16.0.0", &link).is_err());
+
         // Cleanup
         temp_dir.close().unwrap();
     }
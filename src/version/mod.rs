@@ -1,20 +1,40 @@
+pub mod cache;
 pub mod remote;
 pub mod local;
 pub mod compare;
 
-use crate::errors::Result;
+use crate::errors::{NodeError, Result};
 use semver::Version;
+use std::str::FromStr;
 
 #[derive(Debug, Clone)]
 pub struct NodeVersion {
     pub version: Version,
     pub lts: bool,
+    pub lts_codename: Option<String>,
     pub date: String,
+    pub npm: Option<String>,
+    pub v8: Option<String>,
+    pub modules: Option<String>,
+    pub openssl: Option<String>,
+    pub files: Vec<String>,
+    pub security: bool,
 }
 
 impl NodeVersion {
     pub fn new(version: Version, lts: bool, date: String) -> Self {
-        Self { version, lts, date }
+        Self {
+            version,
+            lts,
+            lts_codename: None,
+            date,
+            npm: None,
+            v8: None,
+            modules: None,
+            openssl: None,
+            files: Vec::new(),
+            security: false,
+        }
     }
 
     pub fn version_str(&self) -> String {
@@ -23,16 +43,271 @@ impl NodeVersion {
 }
 
 
+/// Replaces a user-defined alias with its target; built-in specs are never treated as aliases.
+pub fn resolve_alias(version_spec: &str) -> Result<String> {
+    if is_builtin_spec(version_spec) {
+        return Ok(version_spec.to_string());
+    }
+    Ok(crate::config::alias::get_alias(version_spec)?.unwrap_or_else(|| version_spec.to_string()))
+}
+
+fn is_builtin_spec(version_spec: &str) -> bool {
+    let spec = version_spec.to_lowercase();
+    matches!(spec.as_str(), "lts" | "latest" | "node") || spec.starts_with("lts/")
+}
+
 pub async fn get_matching_version(version_spec: &str) -> Result<NodeVersion> {
-    match version_spec {
-        "lts" | "lts/*" => remote::get_latest_lts().await,
-        "latest" | "node" => remote::get_latest().await,
-        _ => {
-            if let Some(version) = crate::config::alias::get_alias(version_spec)? {
-                remote::resolve_version(&version).await
-            } else {
-                remote::resolve_version(version_spec).await
+    let version_spec = resolve_alias(version_spec)?;
+
+    if crate::config::settings::offline() {
+        return pin_lts_offset(&version_spec, &cached_versions())
+            .and_then(|pinned| select_version(&pinned, &get_offline_versions()?))
+            .map_err(|e| match e {
+                NodeError::VersionNotFound(_) => NodeError::OfflineError(format!(
+                    "No installed version matches '{}'. Installing it requires network access.",
+                    version_spec
+                )),
+                e => e,
+            });
+    }
+
+    select_version(&version_spec, &remote::fetch_version_list().await?)
+}
+
+/// Returns the highest installed version satisfying `version_spec`, without touching the network.
+pub fn find_installed_version(version_spec: &str) -> Result<Option<NodeVersion>> {
+    let version_spec = resolve_alias(version_spec)?;
+    let version_spec = match pin_lts_offset(&version_spec, &cached_versions()) {
+        Ok(pinned) => pinned,
+        // Without the index there is no telling which line lts/-N is; let the caller go online.
+        Err(NodeError::VersionNotFound(_)) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    match select_version(&version_spec, &get_offline_versions()?) {
+        Ok(version) => Ok(Some(version)),
+        Err(NodeError::VersionNotFound(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Rewrites `lts/-N` to `lts/<codename>`. The offset counts LTS lines in the release index,
+/// so it means the same thing no matter which versions happen to be installed.
+fn pin_lts_offset(version_spec: &str, index: &[NodeVersion]) -> Result<String> {
+    let is_offset = version_spec
+        .to_lowercase()
+        .strip_prefix("lts/")
+        .is_some_and(|lts_spec| lts_spec.starts_with('-'));
+    if !is_offset {
+        return Ok(version_spec.to_string());
+    }
+
+    let release = select_version(version_spec, index)?;
+    match release.lts_codename {
+        Some(codename) => Ok(format!("lts/{}", codename.to_lowercase())),
+        None => Err(NodeError::VersionNotFound(format!("No LTS release matching {} found", version_spec))),
+    }
+}
+
+fn cached_versions() -> Vec<NodeVersion> {
+    cache::read_cached_index()
+        .and_then(|content| remote::parse_version_list(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Broken installs (see [`local::get_broken_versions`]) that `version_spec` selects: every match
+/// for a version or range, the one `select_version` picks for `lts`, `lts/...` and `latest`.
+pub fn find_broken_versions(version_spec: &str) -> Result<Vec<String>> {
+    let version_spec = pin_lts_offset(&resolve_alias(version_spec)?, &cached_versions())?;
+    let mut broken: Vec<Version> = local::get_broken_versions()?
+        .iter()
+        .filter_map(|v| Version::parse(v).ok())
+        .collect();
+    broken.sort_by(|a, b| b.cmp(a));
+    let broken = with_release_metadata(broken);
+
+    let selected = if is_builtin_spec(&version_spec) {
+        vec![select_version(&version_spec, &broken)?]
+    } else {
+        broken
+            .into_iter()
+            .filter(|v| compare::matches_requirement(&v.version_str(), &version_spec))
+            .collect()
+    };
+    Ok(selected.iter().map(NodeVersion::version_str).collect())
+}
+
+/// Installed versions, annotated with release metadata from the cached index when available.
+fn get_offline_versions() -> Result<Vec<NodeVersion>> {
+    Ok(with_release_metadata(
+        local::get_installed_versions()?.into_iter().map(|installed| installed.version).collect(),
+    ))
+}
+
+fn with_release_metadata(versions: Vec<Version>) -> Vec<NodeVersion> {
+    let index = cached_versions();
+    versions
+        .into_iter()
+        .map(|version| {
+            index
+                .iter()
+                .find(|v| v.version == version)
+                .cloned()
+                .unwrap_or_else(|| NodeVersion::new(version, false, String::new()))
+        })
+        .collect()
+}
+
+/// Picks the best match for `version_spec` from `versions`, which must be sorted newest first.
+pub fn select_version(version_spec: &str, versions: &[NodeVersion]) -> Result<NodeVersion> {
+    let lowercase_spec = version_spec.to_lowercase();
+    match lowercase_spec.as_str() {
+        "lts" => return select_lts("*", versions),
+        spec if spec.starts_with("lts/") => return select_lts(&spec[4..], versions),
+        "latest" | "node" => {
+            return versions
+                .first()
+                .cloned()
+                .ok_or_else(|| NodeError::VersionNotFound("No versions found".to_string()));
+        }
+        _ => {}
+    }
+
+    if let Ok(exact_version) = Version::from_str(version_spec) {
+        return versions
+            .iter()
+            .find(|v| v.version == exact_version)
+            .cloned()
+            .ok_or_else(|| NodeError::VersionNotFound(format!(
+                "Version {} not found. Use 'rnvm list --remote' to see available versions.",
+                version_spec
+            )));
+    }
+
+    // Handle npm ranges (e.g., "12", "12.x", ">=12.0.0", "^18 || ^20")
+    let range = compare::Range::parse(version_spec).map_err(|_| {
+        NodeError::InvalidVersion(format!(
+            "Invalid version specification: {}. Use format like '14', '14.17.0' or '>=14 <16'",
+            version_spec
+        ))
+    })?;
+
+    versions
+        .iter()
+        .find(|v| range.matches(&v.version))
+        .cloned()
+        .ok_or_else(|| NodeError::VersionNotFound(format!("No version matching {} found", version_spec)))
+}
+
+/// Resolves the part after `lts/`: `*` for the newest LTS line, `-N` for the Nth line
+/// before it, or an LTS codename such as `iron`.
+fn select_lts(lts_spec: &str, versions: &[NodeVersion]) -> Result<NodeVersion> {
+    let lts_versions = versions.iter().filter(|v| v.lts);
+
+    let found = if lts_spec == "*" {
+        lts_versions.clone().next()
+    } else if let Some(offset) = lts_spec.strip_prefix('-') {
+        let offset: usize = offset.parse().map_err(|_| {
+            NodeError::InvalidVersion(format!("Invalid LTS offset: lts/{}. Use a form like 'lts/-1'", lts_spec))
+        })?;
+
+        let mut codenames: Vec<&str> = Vec::new();
+        for codename in lts_versions.clone().filter_map(|v| v.lts_codename.as_deref()) {
+            if !codenames.iter().any(|c| c.eq_ignore_ascii_case(codename)) {
+                codenames.push(codename);
             }
         }
+
+        match codenames.get(offset) {
+            Some(codename) => lts_versions.clone().find(|v| v.lts_codename.as_deref() == Some(*codename)),
+            None => None,
+        }
+    } else {
+        lts_versions.clone().find(|v| {
+            v.lts_codename
+                .as_deref()
+                .is_some_and(|codename| codename.eq_ignore_ascii_case(lts_spec))
+        })
+    };
+
+    found.cloned().ok_or_else(|| match lts_spec {
+        "*" => NodeError::VersionNotFound("No LTS version found. Try specifying a version manually.".to_string()),
+        _ => NodeError::VersionNotFound(format!("No LTS release matching lts/{} found", lts_spec)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versions() -> Vec<NodeVersion> {
+        [
+            ("21.6.2", None),
+            ("20.11.1", Some("Iron")),
+            ("20.10.0", Some("Iron")),
+            ("18.19.1", Some("Hydrogen")),
+            ("16.20.2", Some("Gallium")),
+        ]
+        .iter()
+        .map(|(v, codename)| NodeVersion {
+            lts_codename: codename.map(|c| c.to_string()),
+            ..NodeVersion::new(Version::parse(v).unwrap(), codename.is_some(), String::new())
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_select_version() {
+        let versions = versions();
+        assert_eq!(select_version("lts", &versions).unwrap().version_str(), "20.11.1");
+        assert_eq!(select_version("latest", &versions).unwrap().version_str(), "21.6.2");
+        assert_eq!(select_version("20", &versions).unwrap().version_str(), "20.11.1");
+        assert_eq!(select_version("20.10.0", &versions).unwrap().version_str(), "20.10.0");
+        assert_eq!(select_version("18.x", &versions).unwrap().version_str(), "18.19.1");
+        assert_eq!(select_version(">=18.0.0 <20", &versions).unwrap().version_str(), "18.19.1");
+        assert_eq!(select_version("^16 || ^20.10", &versions).unwrap().version_str(), "20.11.1");
+        assert_eq!(select_version("16 - 18", &versions).unwrap().version_str(), "18.19.1");
+        assert!(matches!(select_version("14", &versions), Err(NodeError::VersionNotFound(_))));
+        assert!(matches!(select_version("lts", &[]), Err(NodeError::VersionNotFound(_))));
+        assert!(matches!(select_version("foo", &versions), Err(NodeError::InvalidVersion(_))));
+    }
+
+    #[test]
+    fn test_select_lts_specs() {
+        let versions = versions();
+        assert_eq!(select_version("lts/*", &versions).unwrap().version_str(), "20.11.1");
+        assert_eq!(select_version("lts/iron", &versions).unwrap().version_str(), "20.11.1");
+        assert_eq!(select_version("lts/Hydrogen", &versions).unwrap().version_str(), "18.19.1");
+        assert_eq!(select_version("lts/-0", &versions).unwrap().version_str(), "20.11.1");
+        assert_eq!(select_version("lts/-1", &versions).unwrap().version_str(), "18.19.1");
+        assert_eq!(select_version("lts/-2", &versions).unwrap().version_str(), "16.20.2");
+        assert!(matches!(select_version("lts/-3", &versions), Err(NodeError::VersionNotFound(_))));
+        assert!(matches!(select_version("lts/argon", &versions), Err(NodeError::VersionNotFound(_))));
+        assert!(matches!(select_version("lts/-x", &versions), Err(NodeError::InvalidVersion(_))));
+    }
+
+    #[test]
+    fn test_lts_offset_counts_the_full_index() {
+        let index = versions();
+        let installed: Vec<NodeVersion> = index
+            .iter()
+            .filter(|v| v.lts_codename.as_deref() != Some("Hydrogen"))
+            .cloned()
+            .collect();
+
+        let pinned = pin_lts_offset("lts/-1", &index).unwrap();
+        assert_eq!(pinned, "lts/hydrogen");
+        assert!(matches!(select_version(&pinned, &installed), Err(NodeError::VersionNotFound(_))));
+        assert_eq!(pin_lts_offset("lts/iron", &index).unwrap(), "lts/iron");
+        assert_eq!(pin_lts_offset("20", &[]).unwrap(), "20");
+        assert!(matches!(pin_lts_offset("lts/-1", &[]), Err(NodeError::VersionNotFound(_))));
+    }
+
+    #[test]
+    fn test_builtin_specs_are_not_aliases() {
+        assert!(is_builtin_spec("lts"));
+        assert!(is_builtin_spec("lts/iron"));
+        assert!(is_builtin_spec("LTS/-1"));
+        assert!(is_builtin_spec("node"));
+        assert!(!is_builtin_spec("stable"));
     }
 }
\ No newline at end of file
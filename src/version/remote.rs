@@ -1,24 +1,59 @@
 use crate::errors::{NodeError, Result};
 use crate::version::NodeVersion;
-use reqwest::Client;
-use semver::{Version, VersionReq};
+use reqwest::RequestBuilder;
+use semver::Version;
 use serde_json::Value;
 use std::str::FromStr;
 
 const NODE_DIST_URL: &str = "https://nodejs.org/dist";
 
+/// Returns the dist root: `--mirror`, then `RNVM_NODE_DIST_MIRROR`, then the config file.
+pub fn get_dist_url() -> Result<String> {
+    let configured = crate::config::settings::load_settings()?.mirror;
+    Ok(select_dist_url([
+        crate::config::settings::cli_overrides().mirror.clone(),
+        std::env::var("RNVM_NODE_DIST_MIRROR").ok(),
+        configured,
+    ]))
+}
+
+/// Picks the first non-blank mirror, so an empty `RNVM_NODE_DIST_MIRROR` does not hide the config file.
+fn select_dist_url(mirrors: impl IntoIterator<Item = Option<String>>) -> String {
+    mirrors
+        .into_iter()
+        .flatten()
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .find(|url| !url.is_empty())
+        .unwrap_or_else(|| NODE_DIST_URL.to_string())
+}
+
+/// Attaches mirror credentials from the environment to a request for the dist root.
+///
+/// `RNVM_NODE_DIST_MIRROR_TOKEN` is sent as a bearer token, otherwise
+/// `RNVM_NODE_DIST_MIRROR_USERNAME`/`RNVM_NODE_DIST_MIRROR_PASSWORD` as basic auth.
+/// Credentials are never sent to the public nodejs.org dist root.
+pub fn authorize(request: RequestBuilder, dist_url: &str) -> RequestBuilder {
+    if dist_url == NODE_DIST_URL {
+        return request;
+    }
+
+    if let Ok(token) = std::env::var("RNVM_NODE_DIST_MIRROR_TOKEN") {
+        request.bearer_auth(token)
+    } else if let Ok(username) = std::env::var("RNVM_NODE_DIST_MIRROR_USERNAME") {
+        request.basic_auth(username, std::env::var("RNVM_NODE_DIST_MIRROR_PASSWORD").ok())
+    } else {
+        request
+    }
+}
+
 pub async fn fetch_version_list() -> Result<Vec<NodeVersion>> {
-    let client = Client::new();
-    let response = client
-        .get(&format!("{}/index.json", NODE_DIST_URL))
-        .send()
-        .await
-        .map_err(|e| NodeError::DownloadError(e.to_string()))?;
-
-    let versions: Vec<Value> = response
-        .json()
-        .await
-        .map_err(|e| NodeError::DownloadError(e.to_string()))?;
+    let content = crate::version::cache::load_index().await?;
+    parse_version_list(&content)
+}
+
+pub fn parse_version_list(content: &str) -> Result<Vec<NodeVersion>> {
+    let versions: Vec<Value> = serde_json::from_str(content)
+        .map_err(|e| NodeError::DownloadError(format!("Failed to parse version index: {}", e)))?;
 
     let mut node_versions = Vec::new();
 
@@ -29,13 +64,22 @@ pub async fn fetch_version_list() -> Result<Vec<NodeVersion>> {
         ) {
             let cleaned_version = version_str.trim_start_matches('v');
             if let Ok(version) = Version::from_str(cleaned_version) {
-                let is_lts = version_data["lts"].as_bool().unwrap_or(false) ||
-                    version_data["lts"].is_string();
-                node_versions.push(NodeVersion::new(
-                    version,
-                    is_lts,
-                    date.to_string(),
-                ));
+                let field = |name: &str| version_data[name].as_str().map(|s| s.to_string());
+                let lts_codename = field("lts");
+                node_versions.push(NodeVersion {
+                    lts: version_data["lts"].as_bool().unwrap_or(false) || lts_codename.is_some(),
+                    lts_codename,
+                    npm: field("npm"),
+                    v8: field("v8"),
+                    modules: field("modules"),
+                    openssl: field("openssl"),
+                    files: version_data["files"]
+                        .as_array()
+                        .map(|files| files.iter().filter_map(|f| f.as_str().map(|s| s.to_string())).collect())
+                        .unwrap_or_default(),
+                    security: version_data["security"].as_bool().unwrap_or(false),
+                    ..NodeVersion::new(version, false, date.to_string())
+                });
             }
         }
     }
@@ -44,53 +88,18 @@ pub async fn fetch_version_list() -> Result<Vec<NodeVersion>> {
     Ok(node_versions)
 }
 
-pub async fn get_latest_lts() -> Result<NodeVersion> {
-    let versions = fetch_version_list().await?;
-    versions
-        .into_iter()
-        .find(|v| v.lts)
-        .ok_or_else(|| NodeError::VersionNotFound("No LTS version found. Try specifying a version manually.".to_string()))
-}
-
-pub async fn get_latest() -> Result<NodeVersion> {
-    let versions = fetch_version_list().await?;
-    versions
-        .first()
-        .cloned()
-        .ok_or_else(|| NodeError::VersionNotFound("No versions found".to_string()))
-}
-
-pub async fn resolve_version(version_spec: &str) -> Result<NodeVersion> {
-    if let Ok(exact_version) = Version::from_str(version_spec) {
-        let versions = fetch_version_list().await?;
-        if let Some(version) = versions.into_iter().find(|v| v.version == exact_version) {
-            return Ok(version);
-        }
-        return Err(NodeError::VersionNotFound(format!(
-            "Version {} not found. Use 'nrvm list --remote' to see available versions.",
-            version_spec
+/// Fails with [`NodeError::OfflineError`] when offline mode forbids network access.
+pub fn ensure_online(action: &str) -> Result<()> {
+    if crate::config::settings::offline() {
+        return Err(NodeError::OfflineError(format!(
+            "{} requires network access. Unset RNVM_OFFLINE or drop --offline to continue.",
+            action
         )));
     }
-
-    // Handle version requirements (e.g., "12", "12.x", ">=12.0.0")
-    let req_str = if version_spec.contains('.') || version_spec.contains('>') {
-        version_spec.to_string()
-    } else {
-        format!("^{}.0.0", version_spec)
-    };
-
-    let req = VersionReq::parse(&req_str).map_err(|_| {
-        NodeError::InvalidVersion(format!("Invalid version specification: {}. Use format like '14' or '14.17.0'", version_spec))
-    })?;
-
-    let versions = fetch_version_list().await?;
-    versions
-        .into_iter()
-        .find(|v| req.matches(&v.version))
-        .ok_or_else(|| NodeError::VersionNotFound(format!("No version matching {} found", version_spec)))
+    Ok(())
 }
 
-pub fn get_download_url(version: &NodeVersion) -> String {
+fn platform() -> (&'static str, &'static str, &'static str) {
     let arch = if cfg!(target_arch = "x86_64") {
         "x64"
     } else if cfg!(target_arch = "aarch64") {
@@ -113,21 +122,95 @@ pub fn get_download_url(version: &NodeVersion) -> String {
         "tar.gz"
     };
 
-    format!(
+    (os, arch, ext)
+}
+
+/// Name of this platform's build in the `files` list of `index.json` (e.g. `linux-x64`, `osx-arm64-tar`).
+pub fn get_platform_file_key() -> String {
+    match platform() {
+        ("win", arch, _) => format!("win-{}-zip", arch),
+        ("darwin", arch, _) => format!("osx-{}-tar", arch),
+        (os, arch, _) => format!("{}-{}", os, arch),
+    }
+}
+
+pub fn get_download_url(version: &NodeVersion) -> Result<String> {
+    let (os, arch, ext) = platform();
+
+    Ok(format!(
         "{}/v{}/node-v{}-{}-{}.{}",
-        NODE_DIST_URL,
+        get_dist_url()?,
         version.version_str(),
         version.version_str(),
         os,
         arch,
         ext
-    )
+    ))
+}
+
+pub fn get_shasums_url(version: &NodeVersion) -> Result<String> {
+    Ok(format!("{}/v{}/SHASUMS256.txt", get_dist_url()?, version.version_str()))
+}
+
+pub fn get_shasums_signature_url(version: &NodeVersion) -> Result<String> {
+    Ok(format!("{}.sig", get_shasums_url(version)?))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_select_dist_url() {
+        assert_eq!(select_dist_url([None, None]), NODE_DIST_URL);
+        assert_eq!(
+            select_dist_url([None, Some("https://artifactory.example.com/nodejs/".to_string())]),
+            "https://artifactory.example.com/nodejs"
+        );
+        assert_eq!(
+            select_dist_url([
+                Some("https://mirror.example.com/dist".to_string()),
+                Some("https://artifactory.example.com/nodejs".to_string())
+            ]),
+            "https://mirror.example.com/dist"
+        );
+        assert_eq!(select_dist_url([Some(" ".to_string()), None]), NODE_DIST_URL);
+        assert_eq!(
+            select_dist_url([Some("".to_string()), Some("https://artifactory.example.com/nodejs".to_string())]),
+            "https://artifactory.example.com/nodejs"
+        );
+    }
+
+    #[test]
+    fn test_parse_version_list() {
+        let content = r#"[
+            {"version": "v20.11.1", "date": "2024-02-14", "lts": "Iron", "npm": "10.2.4",
+             "v8": "11.3.244.8", "modules": "115", "openssl": "3.0.13+quic",
+             "files": ["linux-x64", "win-x64-zip"], "security": true},
+            {"version": "v21.6.2", "date": "2024-02-14", "lts": false},
+            {"version": "not-a-version", "date": "2024-02-14", "lts": false}
+        ]"#;
+
+        let versions = parse_version_list(content).unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].version_str(), "21.6.2");
+        assert!(!versions[0].lts);
+        assert!(!versions[0].security);
+        assert_eq!(versions[0].npm, None);
+
+        let iron = &versions[1];
+        assert!(iron.lts);
+        assert_eq!(iron.lts_codename.as_deref(), Some("Iron"));
+        assert_eq!(iron.npm.as_deref(), Some("10.2.4"));
+        assert_eq!(iron.v8.as_deref(), Some("11.3.244.8"));
+        assert_eq!(iron.modules.as_deref(), Some("115"));
+        assert_eq!(iron.openssl.as_deref(), Some("3.0.13+quic"));
+        assert_eq!(iron.files, vec!["linux-x64", "win-x64-zip"]);
+        assert!(iron.security);
+
+        assert!(parse_version_list("not json").is_err());
+    }
+
     #[tokio::test]
     async fn test_fetch_version_list() {
         let versions = fetch_version_list().await.unwrap();
@@ -145,7 +228,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_lts_version() {
-        let lts = get_latest_lts().await.unwrap();
+        let versions = fetch_version_list().await.unwrap();
+        let lts = crate::version::select_version("lts", &versions).unwrap();
         assert!(lts.lts);
     }
 }
\ No newline at end of file